@@ -0,0 +1,157 @@
+//! Plays the AI seats the matchmaker requests on `ai-task-generate-request`, after registering
+//! the personas on `ai-register`.
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use gn_communicator::{models::AIPlayerRegister, rabbitmq::RabbitMQCommunicator, Communicator};
+use lapin::{
+    message::Delivery,
+    options::{BasicAckOptions, BasicConsumeOptions, BasicNackOptions, QueueDeclareOptions},
+    types::FieldTable,
+    Connection, ConnectionProperties, Consumer,
+};
+use tracing::{error, info, warn, Level};
+use tracing_subscriber::FmtSubscriber;
+
+mod models;
+mod player;
+
+use models::Task;
+use schnapsen_rs::agent::{Persona, PERSONAS};
+
+const AI_TASK_QUEUE: &str = "ai-task-generate-request";
+
+const RETRY_DELAY: Duration = Duration::from_secs(3);
+
+/// The modes every persona is registered for.
+const MODES: [&str; 2] = ["speed", "bummerl"];
+
+async fn register_personas(communicator: &RabbitMQCommunicator) {
+    for persona in &PERSONAS {
+        for mode in MODES {
+            let ai_player = AIPlayerRegister {
+                game: "Schnapsen".to_string(),
+                mode: mode.to_string(),
+                elo: persona.elo,
+                display_name: persona.display_name.to_string(),
+            };
+            communicator.register_ai_player(&ai_player).await;
+        }
+    }
+    info!("AI players registered");
+}
+
+/// Takes the task if it is for Schnapsen, otherwise leaves it to another worker.
+async fn on_task(delivery: Delivery) {
+    let task: Task = match serde_json::from_slice(&delivery.data) {
+        Ok(task) => task,
+        Err(err) => {
+            error!("Dropping malformed task: {:?}", err);
+            let _ = delivery.ack(BasicAckOptions::default()).await;
+            return;
+        }
+    };
+
+    if task.game != "Schnapsen" {
+        let requeue = BasicNackOptions {
+            requeue: true,
+            ..Default::default()
+        };
+        if let Err(err) = delivery.nack(requeue).await {
+            error!("Failed to nack task: {:?}", err);
+        }
+        return;
+    }
+
+    let persona = Persona::find(&task.ai_id).unwrap_or_else(|| {
+        warn!(
+            "Unknown persona {}, playing as {}",
+            task.ai_id, PERSONAS[0].display_name
+        );
+        &PERSONAS[0]
+    });
+
+    if let Err(err) = delivery.ack(BasicAckOptions::default()).await {
+        error!("Failed to ack task: {:?}", err);
+        return;
+    }
+
+    info!(
+        "{} takes a seat in {} match {}",
+        persona.display_name, task.mode, task.read
+    );
+    if let Err(err) = player::play(task, persona).await {
+        error!("{} failed to play: {}", persona.display_name, err);
+    }
+}
+
+/// Declares the task queue and starts consuming it. The connection has to be kept alive for as
+/// long as the consumer is used.
+async fn subscribe(amqp_url: &str) -> lapin::Result<(Connection, Consumer)> {
+    let conn = Connection::connect(amqp_url, ConnectionProperties::default()).await?;
+    let channel = conn.create_channel().await?;
+    channel
+        .queue_declare(
+            AI_TASK_QUEUE,
+            QueueDeclareOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+    let consumer = channel
+        .basic_consume(
+            AI_TASK_QUEUE,
+            "",
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+    Ok((conn, consumer))
+}
+
+/// Consumes the task queue, reconnecting whenever the connection drops.
+async fn consume_tasks(amqp_url: &str) {
+    loop {
+        let (_conn, mut consumer) = match subscribe(amqp_url).await {
+            Ok(subscription) => subscription,
+            Err(err) => {
+                error!("Could not consume {}: {:?}", AI_TASK_QUEUE, err);
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        info!("Listening on queue: {}", AI_TASK_QUEUE);
+        while let Some(delivery) = consumer.next().await {
+            match delivery {
+                Ok(delivery) => {
+                    tokio::spawn(on_task(delivery));
+                }
+                Err(err) => {
+                    error!("Consumer error, reconnecting: {:?}", err);
+                    break;
+                }
+            }
+        }
+
+        warn!("Consumer of {} ended, reconnecting", AI_TASK_QUEUE);
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let level = match std::env::var("DEBUG").as_deref() {
+        Ok("true") | Ok("1") => Level::DEBUG,
+        _ => Level::INFO,
+    };
+    let subscriber = FmtSubscriber::builder().with_max_level(level).finish();
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+    info!("Starting Schnapsen AI Worker");
+
+    let amqp_url = std::env::var("AMQP_URL").expect("AMQP_URL must be set");
+    let communicator = RabbitMQCommunicator::connect(&amqp_url).await;
+    register_personas(&communicator).await;
+
+    consume_tasks(&amqp_url).await;
+}
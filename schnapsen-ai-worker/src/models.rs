@@ -0,0 +1,25 @@
+use serde::Deserialize;
+
+/// A request of the matchmaker to fill a seat of a match with an AI player.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Task {
+    pub ai_id: String,
+    pub game: String,
+    pub mode: String,
+    pub address: String,
+    /// The id of the match, which is also its namespace on the server.
+    pub read: String,
+    /// The write token of the AI's seat.
+    pub write: String,
+}
+
+impl Task {
+    /// The address of the server, which the matchmaker may send without a scheme.
+    pub fn url(&self) -> String {
+        if self.address.starts_with("http://") || self.address.starts_with("https://") {
+            self.address.clone()
+        } else {
+            format!("http://{}", self.address)
+        }
+    }
+}
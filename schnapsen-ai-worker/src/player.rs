@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use rand::seq::SliceRandom;
+use schnapsen_duo_client::{ClientError, DuoClient, ServerEvent};
+use schnapsen_rs::{agent::Persona, models::Action};
+use tokio::time::timeout;
+use tracing::{debug, info, warn};
+
+use crate::models::Task;
+
+/// A match without any event for this long is given up.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// The actions the server rejected since the table last changed, so they aren't tried again.
+#[derive(Default)]
+struct Rejected {
+    last: Option<Action>,
+    actions: Vec<Action>,
+}
+
+impl Rejected {
+    fn on_event(&mut self, event: &ServerEvent) {
+        match event {
+            ServerEvent::Error(message) => {
+                warn!("Server rejected {:?}: {}", self.last, message);
+                self.actions.extend(self.last.take());
+            }
+            ServerEvent::Public(_) => self.actions.clear(),
+            _ => {}
+        }
+    }
+}
+
+/// Waits until no event arrived for `quiet`. `None` if the connection is gone.
+async fn settle(
+    events: &mut (impl Stream<Item = ServerEvent> + Unpin),
+    rejected: &mut Rejected,
+    quiet: Duration,
+) -> Option<()> {
+    loop {
+        match timeout(quiet, events.next()).await {
+            Ok(Some(event)) => rejected.on_event(&event),
+            Ok(None) => return None,
+            Err(_) => return Some(()),
+        }
+    }
+}
+
+/// Plays the seat of the task until the match is over.
+pub async fn play(task: Task, persona: &'static Persona) -> Result<(), ClientError> {
+    let mut agent = persona.agent(rand::random());
+    let client = DuoClient::connect(&task.url(), &task.read, &task.write).await?;
+    let mut events = client.events();
+    let mut rejected = Rejected::default();
+    info!("{} joined match {}", persona.display_name, task.read);
+
+    loop {
+        let event = match timeout(IDLE_TIMEOUT, events.next()).await {
+            Ok(Some(event)) => event,
+            Ok(None) => break,
+            Err(_) => {
+                warn!("Giving up match {}, nothing happened", task.read);
+                break;
+            }
+        };
+        rejected.on_event(&event);
+        if client.mirror().is_finished() {
+            break;
+        }
+
+        // Engine events arrive in bursts and not necessarily in order
+        if settle(&mut events, &mut rejected, persona.think)
+            .await
+            .is_none()
+        {
+            break;
+        }
+
+        let (observation, mut legal) = {
+            let mirror = client.mirror();
+            (mirror.observation(), mirror.legal_actions())
+        };
+        legal.retain(|action| !rejected.actions.contains(action));
+        if legal.is_empty() {
+            continue;
+        }
+        let Some(observation) = observation else {
+            // Cutting and taking up the cards come before the deal, with nothing to think about
+            let action = *legal.choose(&mut rand::thread_rng()).unwrap();
+            debug!("{} opens with {:?}", persona.display_name, action);
+            rejected.last = Some(action);
+            client.perform(action).await?;
+            continue;
+        };
+
+        // A search may take a while, which must not block the socket
+        let (returned, action) = tokio::task::spawn_blocking(move || {
+            let action = agent.act(&observation, &legal);
+            (agent, action)
+        })
+        .await
+        .expect("Agent panicked");
+        agent = returned;
+
+        debug!("{} performs {:?}", persona.display_name, action);
+        rejected.last = Some(action);
+        client.perform(action).await?;
+    }
+
+    info!("{} left match {}", persona.display_name, task.read);
+    client.disconnect().await
+}
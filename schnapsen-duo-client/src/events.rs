@@ -0,0 +1,118 @@
+use schnapsen_rs::{PrivateEvent, PublicEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// An event as the server emits it, with the time it happened in microseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedEvent<T> {
+    #[serde(flatten)]
+    pub event: T,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GameEvent {
+    Private(TimedEvent<PrivateEvent>),
+    Public(TimedEvent<PublicEvent>),
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeoutThreat {
+    timeout: u64,
+    timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeoutWarning {
+    remaining: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Seat {
+    user_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Timeout {
+    user_id: String,
+    reason: String,
+}
+
+/// Everything the server sends to a player of a match.
+#[derive(Debug, Clone)]
+pub enum ServerEvent {
+    Public(TimedEvent<PublicEvent>),
+    Private(TimedEvent<PrivateEvent>),
+    /// The player has `timeout` seconds left to move.
+    TimeoutThreat {
+        timeout: u64,
+        timestamp: u64,
+    },
+    /// Only `remaining` seconds are left to move.
+    TimeoutWarning {
+        remaining: u64,
+    },
+    TimeoutThreatCancelled,
+    Timeout {
+        user_id: String,
+        reason: String,
+    },
+    /// A new round of the bummerl is about to be dealt.
+    Reset,
+    /// The server accepted the write token for the seat of `user_id`, the id the events name the
+    /// player by.
+    Seat {
+        user_id: String,
+    },
+    Error(String),
+    /// The connection was (re-)established and the player is about to authenticate. The server
+    /// replays the events of the current round after this.
+    Connected,
+    Disconnected,
+}
+
+impl ServerEvent {
+    /// Parses the data the server emitted as `event`. Unknown events are `None`.
+    pub fn parse(event: &str, data: Value) -> Option<Self> {
+        let event = match event {
+            "threaten_timeout" => {
+                let threat: TimeoutThreat = serde_json::from_value(data).ok()?;
+                ServerEvent::TimeoutThreat {
+                    timeout: threat.timeout,
+                    timestamp: threat.timestamp,
+                }
+            }
+            "timeout_warning" => {
+                let warning: TimeoutWarning = serde_json::from_value(data).ok()?;
+                ServerEvent::TimeoutWarning {
+                    remaining: warning.remaining,
+                }
+            }
+            "cancel_timeout_threat" => ServerEvent::TimeoutThreatCancelled,
+            "timeout" => {
+                let timeout: Timeout = serde_json::from_value(data).ok()?;
+                ServerEvent::Timeout {
+                    user_id: timeout.user_id,
+                    reason: timeout.reason,
+                }
+            }
+            "reset" => ServerEvent::Reset,
+            "seat" => {
+                let seat: Seat = serde_json::from_value(data).ok()?;
+                ServerEvent::Seat {
+                    user_id: seat.user_id,
+                }
+            }
+            "error" => ServerEvent::Error(match data {
+                Value::String(message) => message,
+                other => other.to_string(),
+            }),
+            _ => match serde_json::from_value(data).ok()? {
+                GameEvent::Private(event) => ServerEvent::Private(event),
+                GameEvent::Public(event) => ServerEvent::Public(event),
+            },
+        };
+        Some(event)
+    }
+}
@@ -0,0 +1,229 @@
+//! Async client for the socket.io protocol of the duo server, so Rust bots and tools can play on
+//! a live server.
+//!
+//! A `DuoClient` joins the namespace `/{match_id}` and authenticates with the write token of a
+//! player, again after every reconnect. The server's events are mirrored into a `Mirror`, which
+//! gives the same `Observation` and legal actions the engine would, and are also available as
+//! typed streams.
+//!
+//! ```no_run
+//! # async fn play() -> Result<(), schnapsen_duo_client::ClientError> {
+//! use futures_util::StreamExt;
+//! use schnapsen_duo_client::DuoClient;
+//!
+//! let client = DuoClient::connect("http://localhost:6060", "12345", "token").await?;
+//! let mut events = client.private_events();
+//! while let Some(_event) = events.next().await {
+//!     let action = client.mirror().legal_actions().first().copied();
+//!     if let Some(action) = action {
+//!         client.perform(action).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures_util::{future, FutureExt, Stream, StreamExt};
+use rust_socketio::{
+    asynchronous::{Client, ClientBuilder},
+    Event, Payload,
+};
+use schnapsen_rs::{
+    models::{Action, Named, Naming},
+    PrivateEvent, PublicEvent,
+};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+
+mod events;
+mod mirror;
+#[cfg(test)]
+mod tests;
+
+pub use events::{ServerEvent, TimedEvent};
+pub use mirror::Mirror;
+
+/// Events buffered for every stream. Slower streams skip the oldest events.
+const EVENT_BUFFER: usize = 256;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Socket error: {0}")]
+    Socket(Box<rust_socketio::Error>),
+}
+
+impl From<rust_socketio::Error> for ClientError {
+    fn from(err: rust_socketio::Error) -> Self {
+        ClientError::Socket(Box::new(err))
+    }
+}
+
+/// The socket.io event and data of an action, as the server's translator expects them, with the
+/// cards named by `naming`.
+fn action_message(action: Action, naming: Naming) -> (&'static str, Value) {
+    match action {
+        Action::PlayCard(card) => ("play_card", json!(Named::new(&card, naming))),
+        Action::SwapTrump(card) => ("swap_trump", json!(Named::new(&card, naming))),
+        Action::CloseTalon => ("close_talon", Value::Null),
+        Action::Announce20(cards) => ("announce_20", json!(Named::new(&cards, naming))),
+        Action::Announce40 => ("announce_40", Value::Null),
+        Action::CuttDeck(idx) => ("cutt_deck", json!(idx)),
+        Action::TakeCards(idx) => ("take_cards", json!(idx)),
+    }
+}
+
+struct Shared {
+    mirror: Mutex<Mirror>,
+    events: broadcast::Sender<ServerEvent>,
+}
+
+impl Shared {
+    /// Mirrors the event before anyone is notified, so the mirror is never behind a stream.
+    fn dispatch(&self, event: ServerEvent) {
+        self.mirror.lock().unwrap().on_event(&event);
+        let _ = self.events.send(event);
+    }
+}
+
+/// A player's connection to a match of the duo server.
+pub struct DuoClient {
+    socket: Client,
+    shared: Arc<Shared>,
+    naming: Naming,
+}
+
+impl DuoClient {
+    /// Connects to the match at `url` and authenticates with the player's write token.
+    pub async fn connect(url: &str, match_id: &str, token: &str) -> Result<Self, ClientError> {
+        // Who the player is only shows once the server accepted the token
+        let shared = Arc::new(Shared {
+            mirror: Mutex::new(Mirror::new(String::new())),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        });
+
+        let on_any = shared.clone();
+        let on_error = shared.clone();
+        let on_connect = shared.clone();
+        let on_close = shared.clone();
+        let token = token.to_string();
+
+        let socket = ClientBuilder::new(url)
+            .namespace(format!("/{match_id}"))
+            .on_any(move |event, payload, _| {
+                let shared = on_any.clone();
+                async move { Self::forward(&shared, event, payload) }.boxed()
+            })
+            .on(Event::Error, move |payload, _| {
+                let shared = on_error.clone();
+                async move { Self::forward(&shared, Event::Error, payload) }.boxed()
+            })
+            .on(Event::Connect, move |_, socket| {
+                let shared = on_connect.clone();
+                let token = token.clone();
+                async move {
+                    // Forget the round before the server replays it
+                    shared.dispatch(ServerEvent::Connected);
+                    // The token has to stay a string, even if it looks like a number
+                    if let Err(err) = socket.emit("auth", Value::String(token)).await {
+                        shared.dispatch(ServerEvent::Error(err.to_string()));
+                    }
+                }
+                .boxed()
+            })
+            .on(Event::Close, move |_, _| {
+                let shared = on_close.clone();
+                async move { shared.dispatch(ServerEvent::Disconnected) }.boxed()
+            })
+            .connect()
+            .await?;
+
+        Ok(Self {
+            socket,
+            shared,
+            naming: Naming::French,
+        })
+    }
+
+    /// Sends the cards of the actions with the names of `naming`. The server reads every naming,
+    /// and so does the client.
+    pub fn with_naming(mut self, naming: Naming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    fn forward(shared: &Shared, event: Event, payload: Payload) {
+        let Payload::Text(values) = payload else {
+            return;
+        };
+        let data = values.into_iter().next().unwrap_or(Value::Null);
+        if let Some(event) = ServerEvent::parse(event.as_str(), data) {
+            shared.dispatch(event);
+        }
+    }
+
+    /// The round as the player sees it, up to the last received event.
+    pub fn mirror(&self) -> MutexGuard<'_, Mirror> {
+        self.shared.mirror.lock().unwrap()
+    }
+
+    /// Every event received from now on.
+    pub fn events(&self) -> impl Stream<Item = ServerEvent> + Unpin {
+        BroadcastStream::new(self.shared.events.subscribe())
+            .filter_map(|event| future::ready(event.ok()))
+    }
+
+    pub fn public_events(&self) -> impl Stream<Item = TimedEvent<PublicEvent>> + Unpin {
+        self.events().filter_map(|event| {
+            future::ready(match event {
+                ServerEvent::Public(event) => Some(event),
+                _ => None,
+            })
+        })
+    }
+
+    pub fn private_events(&self) -> impl Stream<Item = TimedEvent<PrivateEvent>> + Unpin {
+        self.events().filter_map(|event| {
+            future::ready(match event {
+                ServerEvent::Private(event) => Some(event),
+                _ => None,
+            })
+        })
+    }
+
+    /// Sends the action to the server. Whether it was accepted shows in the events, a rejected
+    /// action is answered with a `ServerEvent::Error`.
+    pub async fn perform(&self, action: Action) -> Result<(), ClientError> {
+        let (event, data) = action_message(action, self.naming);
+        self.socket.emit(event, data).await?;
+        Ok(())
+    }
+
+    pub async fn draw_card(&self) -> Result<(), ClientError> {
+        self.socket.emit("draw_card", Value::Null).await?;
+        Ok(())
+    }
+
+    /// Gives up the match.
+    pub async fn quit(&self) -> Result<(), ClientError> {
+        self.socket.emit("quit", Value::Null).await?;
+        Ok(())
+    }
+
+    /// Asks the server to replay the events since `timestamp`, or the whole round if `None`.
+    pub async fn sync(&self, timestamp: Option<u64>) -> Result<(), ClientError> {
+        if timestamp.is_none() {
+            self.mirror().reset_round();
+        }
+        self.socket.emit("sync", json!(timestamp)).await?;
+        Ok(())
+    }
+
+    pub async fn disconnect(&self) -> Result<(), ClientError> {
+        self.socket.disconnect().await?;
+        Ok(())
+    }
+}
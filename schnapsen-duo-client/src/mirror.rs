@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+
+use schnapsen_rs::{
+    agent::Observation,
+    knowledge::KnowledgeTracker,
+    models::{Action, AnnounceType, Announcement, Card, CardSet},
+    Opening, PrivateEvent, PublicEvent,
+};
+
+use crate::events::ServerEvent;
+
+/// Number of cards in a game.
+const DECK_SIZE: usize = 20;
+
+/// The round as far as one player can see it, rebuilt from the events the server sends them.
+/// Events have to be passed in the order they arrived.
+#[derive(Debug, Clone)]
+pub struct Mirror {
+    player_id: String,
+    opponent_id: Option<String>,
+    knowledge: KnowledgeTracker,
+    playable: CardSet,
+    announcable: Vec<Announcement>,
+    swap: Option<Card>,
+    active: Option<String>,
+    trick: Vec<(String, Card)>,
+    last_trick: Option<(String, [Card; 2])>,
+    won: CardSet,
+    opponent_won: CardSet,
+    announcements: Vec<Announcement>,
+    opponent_announcements: Vec<Announcement>,
+    /// Whether the player announced and has yet to play one of the announced cards.
+    announced: bool,
+    /// What the player has to decide before the cards are dealt.
+    opening: Option<Opening>,
+    deck_size: usize,
+    closed_by: Option<String>,
+    points: HashMap<String, u8>,
+    game_points: HashMap<String, u8>,
+    finished: bool,
+}
+
+impl Mirror {
+    pub fn new(player_id: impl Into<String>) -> Self {
+        let player_id = player_id.into();
+        Self {
+            knowledge: KnowledgeTracker::new(player_id.clone()),
+            player_id,
+            opponent_id: None,
+            playable: CardSet::EMPTY,
+            announcable: Vec::new(),
+            swap: None,
+            active: None,
+            trick: Vec::new(),
+            last_trick: None,
+            won: CardSet::EMPTY,
+            opponent_won: CardSet::EMPTY,
+            announcements: Vec::new(),
+            opponent_announcements: Vec::new(),
+            announced: false,
+            opening: None,
+            deck_size: 0,
+            closed_by: None,
+            points: HashMap::new(),
+            game_points: HashMap::new(),
+            finished: false,
+        }
+    }
+
+    /// Forgets everything about the current round, but keeps the game points.
+    pub fn reset_round(&mut self) {
+        *self = Self {
+            opponent_id: self.opponent_id.take(),
+            game_points: std::mem::take(&mut self.game_points),
+            finished: self.finished,
+            ..Self::new(std::mem::take(&mut self.player_id))
+        };
+    }
+
+    /// Plays as `player_id` from now on. The server replays the round after assigning the seat.
+    fn take_seat(&mut self, player_id: &str) {
+        if self.player_id != player_id {
+            self.player_id = player_id.to_string();
+            self.reset_round();
+        }
+    }
+
+    pub fn on_event(&mut self, event: &ServerEvent) {
+        match event {
+            ServerEvent::Public(event) => self.on_pub_event(&event.event),
+            ServerEvent::Private(event) => self.on_priv_event(&event.event),
+            ServerEvent::Reset | ServerEvent::Connected => self.reset_round(),
+            ServerEvent::Timeout { .. } => self.finished = true,
+            ServerEvent::Seat { user_id } => self.take_seat(user_id),
+            _ => {}
+        }
+    }
+
+    pub fn on_priv_event(&mut self, event: &PrivateEvent) {
+        self.knowledge.on_priv_event(event);
+        match event {
+            PrivateEvent::CardPlayable(card) => {
+                self.playable.insert(*card);
+            }
+            PrivateEvent::CardNotPlayable(card) => {
+                self.playable.remove(*card);
+            }
+            PrivateEvent::CanAnnounce(announcement) if !self.announcable.contains(announcement) => {
+                self.announcable.push(announcement.clone());
+            }
+            PrivateEvent::CannotAnnounce(announcement) => {
+                self.announcable.retain(|other| other != announcement)
+            }
+            PrivateEvent::TrumpChangePossible(card) => self.swap = Some(*card),
+            PrivateEvent::TrumpChangeImpossible(_) => self.swap = None,
+            PrivateEvent::AllowCuttDeck => self.opening = Some(Opening::Cut),
+            PrivateEvent::AllowDrawCard => self.opening = Some(Opening::Draw),
+            _ => {}
+        }
+    }
+
+    fn see(&mut self, user_id: &str) {
+        if user_id != self.player_id && self.opponent_id.is_none() {
+            self.opponent_id = Some(user_id.to_string());
+        }
+    }
+
+    pub fn on_pub_event(&mut self, event: &PublicEvent) {
+        self.knowledge.on_pub_event(event);
+        match event {
+            PublicEvent::Active { user_id } => {
+                self.see(user_id);
+                self.active = Some(user_id.clone());
+            }
+            PublicEvent::Inactive { user_id } => {
+                self.see(user_id);
+                if self.active.as_ref() == Some(user_id) {
+                    self.active = None;
+                }
+            }
+            PublicEvent::PlayCard { user_id, card } => {
+                self.see(user_id);
+                if *user_id == self.player_id {
+                    self.announced = false;
+                }
+                // The trick may have been reported before its last card
+                let finished = self
+                    .last_trick
+                    .as_ref()
+                    .is_some_and(|(_, cards)| cards.contains(card));
+                if !finished {
+                    self.trick.push((user_id.clone(), *card));
+                }
+            }
+            PublicEvent::Trick { user_id, cards } => {
+                self.see(user_id);
+                self.trick.retain(|(_, card)| !cards.contains(card));
+                if *user_id == self.player_id {
+                    self.won |= CardSet::from(cards.as_slice());
+                } else {
+                    self.opponent_won |= CardSet::from(cards.as_slice());
+                }
+                self.last_trick = Some((user_id.clone(), *cards));
+            }
+            PublicEvent::Announce {
+                user_id,
+                announcement,
+            } => {
+                self.see(user_id);
+                if *user_id == self.player_id {
+                    self.announced = true;
+                    self.announcements.push(announcement.clone());
+                } else {
+                    self.opponent_announcements.push(announcement.clone());
+                }
+            }
+            PublicEvent::CloseTalon { user_id } => {
+                self.see(user_id);
+                self.closed_by = Some(user_id.clone());
+            }
+            PublicEvent::DeckCardCount(count) => self.deck_size = *count,
+            PublicEvent::Score { user_id, points } => {
+                self.see(user_id);
+                self.points.insert(user_id.clone(), *points);
+            }
+            PublicEvent::Result { ranked, .. } => {
+                self.game_points = ranked.clone();
+                self.active = None;
+                self.playable = CardSet::EMPTY;
+                self.announcable.clear();
+                self.swap = None;
+            }
+            PublicEvent::FinalResult { ranked, .. } => {
+                self.game_points = ranked.clone();
+                self.finished = true;
+            }
+            // Turning up the trump card ends the opening
+            PublicEvent::TrumpChange(Some(_)) => self.opening = None,
+            PublicEvent::TrumpChange(None)
+            | PublicEvent::ReceiveCard { .. }
+            | PublicEvent::FinishedDistribution
+            | PublicEvent::CuttDeck { .. } => {}
+        }
+    }
+
+    #[inline]
+    pub fn player_id(&self) -> &str {
+        &self.player_id
+    }
+
+    #[inline]
+    pub fn opponent_id(&self) -> Option<&str> {
+        self.opponent_id.as_deref()
+    }
+
+    /// The card counting of the player.
+    #[inline]
+    pub fn knowledge(&self) -> &KnowledgeTracker {
+        &self.knowledge
+    }
+
+    #[inline]
+    pub fn hand(&self) -> CardSet {
+        self.knowledge.hand()
+    }
+
+    /// The cards of the hand which may be played right now.
+    #[inline]
+    pub fn playable(&self) -> CardSet {
+        self.playable & self.hand()
+    }
+
+    /// The marriages the player could announce.
+    #[inline]
+    pub fn announcable(&self) -> &[Announcement] {
+        &self.announcable
+    }
+
+    /// The jack of trump, if it can be swapped for the trump card.
+    #[inline]
+    pub fn swap(&self) -> Option<Card> {
+        self.swap
+    }
+
+    #[inline]
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// What the player has to decide before the cards are dealt, if the round waits for them.
+    #[inline]
+    pub fn opening(&self) -> Option<Opening> {
+        self.opening
+    }
+
+    #[inline]
+    pub fn is_my_turn(&self) -> bool {
+        self.active.as_deref() == Some(self.player_id.as_str())
+    }
+
+    /// The cards of the trick being played and who played them, in order.
+    #[inline]
+    pub fn trick(&self) -> &[(String, Card)] {
+        &self.trick
+    }
+
+    /// The winner and the cards of the last finished trick.
+    #[inline]
+    pub fn last_trick(&self) -> Option<&(String, [Card; 2])> {
+        self.last_trick.as_ref()
+    }
+
+    /// Number of face-down cards in the talon.
+    #[inline]
+    pub fn deck_size(&self) -> usize {
+        self.deck_size
+    }
+
+    #[inline]
+    pub fn closed_by(&self) -> Option<&str> {
+        self.closed_by.as_deref()
+    }
+
+    /// The points of the round, as the server last reported them.
+    #[inline]
+    pub fn points(&self) -> &HashMap<String, u8> {
+        &self.points
+    }
+
+    /// The game points of the bummerl after the last finished round.
+    #[inline]
+    pub fn game_points(&self) -> &HashMap<String, u8> {
+        &self.game_points
+    }
+
+    /// Whether the match is over, because a bummerl was won or a player timed out.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The card the opponent led, if the player has to respond to it.
+    fn lead(&self) -> Option<Card> {
+        match self.trick.as_slice() {
+            [(user_id, card)] if *user_id != self.player_id => Some(*card),
+            _ => None,
+        }
+    }
+
+    /// Points of the won tricks. Announcements only count once a trick was won.
+    fn trick_points(won: CardSet, announcements: &[Announcement]) -> u8 {
+        let tricks: u8 = won.iter().map(|card| card.points()).sum();
+        if won.is_empty() {
+            return tricks;
+        }
+        let announced: u8 = announcements
+            .iter()
+            .map(|announcement| announcement.announce_type as u8)
+            .sum();
+        tricks + announced
+    }
+
+    /// The same observation the engine would give the player. `None` until the cards are dealt.
+    pub fn observation(&self) -> Option<Observation> {
+        let trump = self.knowledge.trump()?;
+        let hand = self.hand();
+        let trump_card = self.knowledge.trump_card();
+
+        // Every card is in a hand, played, the trump card or in the talon
+        let opponent_hand_size = DECK_SIZE.saturating_sub(
+            hand.len() as usize
+                + self.knowledge.played().len() as usize
+                + usize::from(trump_card.is_some())
+                + self.deck_size,
+        );
+
+        Some(Observation {
+            player_id: self.player_id.clone(),
+            hand,
+            playable: self.playable(),
+            trump,
+            trump_card,
+            deck_size: self.deck_size,
+            closed_talon: self.closed_by.clone(),
+            lead: self.lead(),
+            won: self.won,
+            opponent_won: self.opponent_won,
+            points: Self::trick_points(self.won, &self.announcements),
+            opponent_points: Self::trick_points(self.opponent_won, &self.opponent_announcements),
+            announcements: self.announcements.clone(),
+            opponent_announcements: self.opponent_announcements.clone(),
+            opponent_hand_size,
+            opponent_revealed: self.knowledge.opponent_cards(),
+            opponent_ruled_out: self.knowledge.ruled_out(),
+        })
+    }
+
+    /// All actions the player can take right now, like `SchnapsenDuo::legal_actions`. Empty if it
+    /// isn't their turn.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if !self.is_my_turn() {
+            return Vec::new();
+        }
+        if let Some(opening) = self.opening {
+            return opening.actions();
+        }
+
+        let mut actions: Vec<_> = self.playable().iter().map(Action::PlayCard).collect();
+
+        // After announcing, one of the announced cards has to be played
+        if !self.trick.is_empty() || self.announced {
+            return actions;
+        }
+
+        let trump = self.knowledge.trump();
+        for announcement in &self.announcable {
+            // A marriage in trump always counts forty
+            let trump_twenty = announcement.announce_type == AnnounceType::Twenty
+                && trump == Some(announcement.suit());
+            if trump_twenty
+                || self
+                    .announcements
+                    .iter()
+                    .any(|announced| announced.suit() == announcement.suit())
+            {
+                continue;
+            }
+            actions.push(match announcement.announce_type {
+                AnnounceType::Twenty => Action::Announce20(announcement.cards),
+                AnnounceType::Forty => Action::Announce40,
+            });
+        }
+
+        if let Some(card) = self.swap {
+            actions.push(Action::SwapTrump(card));
+        }
+
+        if self.closed_by.is_none() && self.deck_size > 0 {
+            actions.push(Action::CloseTalon);
+        }
+
+        actions
+    }
+}
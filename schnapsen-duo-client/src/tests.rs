@@ -0,0 +1,230 @@
+use schnapsen_rs::{
+    models::{Action, AnnounceType, Announcement, Card, CardSet, CardSuit, Naming},
+    Opening, PrivateEvent, PublicEvent,
+};
+use serde_json::json;
+
+use crate::{action_message, Mirror, ServerEvent};
+
+fn card(card: &str) -> Card {
+    card.parse().unwrap()
+}
+
+fn cards(cards: &str) -> CardSet {
+    cards.split_whitespace().map(card).collect()
+}
+
+/// The mirror of player "1" after the deal, with "1" to move.
+fn dealt(hand: &str, trump: &str) -> Mirror {
+    let mut mirror = Mirror::new("1");
+    for card in cards(hand) {
+        mirror.on_priv_event(&PrivateEvent::CardAvailabe(card));
+        mirror.on_priv_event(&PrivateEvent::CardPlayable(card));
+    }
+    mirror.on_pub_event(&PublicEvent::TrumpChange(Some(self::card(trump))));
+    mirror.on_pub_event(&PublicEvent::DeckCardCount(9));
+    mirror.on_pub_event(&PublicEvent::Active {
+        user_id: "1".to_string(),
+    });
+    mirror
+}
+
+fn play(mirror: &mut Mirror, user_id: &str, card: &str) {
+    mirror.on_pub_event(&PublicEvent::PlayCard {
+        user_id: user_id.to_string(),
+        card: self::card(card),
+    });
+}
+
+#[test]
+fn parses_the_server_events() {
+    let event = json!({
+        "event": "PlayCard",
+        "data": { "user_id": "2", "card": { "value": "Ace", "suit": "Hearts" } },
+        "timestamp": 42,
+    });
+    match ServerEvent::parse("play_card", event) {
+        Some(ServerEvent::Public(event)) => {
+            assert_eq!(event.timestamp, 42);
+            assert!(
+                matches!(event.event, PublicEvent::PlayCard { card, .. } if card == self::card("HA"))
+            );
+        }
+        other => panic!("expected a public event, got {other:?}"),
+    }
+
+    let event = json!({ "event": "AllowPlayCard", "timestamp": 7 });
+    assert!(matches!(
+        ServerEvent::parse("allow_play_card", event),
+        Some(ServerEvent::Private(event)) if matches!(event.event, PrivateEvent::AllowPlayCard)
+    ));
+
+    let threat = json!({ "timeout": 30, "timestamp": 1 });
+    assert!(matches!(
+        ServerEvent::parse("threaten_timeout", threat),
+        Some(ServerEvent::TimeoutThreat { timeout: 30, .. })
+    ));
+    let warning = json!({ "remaining": 5, "decision": "play_card", "timestamp": 2 });
+    assert!(matches!(
+        ServerEvent::parse("timeout_warning", warning),
+        Some(ServerEvent::TimeoutWarning { remaining: 5 })
+    ));
+    assert!(matches!(
+        ServerEvent::parse("error", json!("Not your turn")),
+        Some(ServerEvent::Error(message)) if message == "Not your turn"
+    ));
+    assert!(ServerEvent::parse("unknown", json!({})).is_none());
+}
+
+#[test]
+fn sends_the_cards_with_the_naming() {
+    let (event, data) = action_message(Action::PlayCard(card("CJ")), Naming::German);
+    assert_eq!(event, "play_card");
+    assert_eq!(data, json!({ "value": "Unter", "suit": "Eichel" }));
+    assert_eq!(serde_json::from_value::<Card>(data).unwrap(), card("CJ"));
+
+    let (_, data) = action_message(Action::SwapTrump(card("CJ")), Naming::French);
+    assert_eq!(data, json!({ "value": "Jack", "suit": "Clubs" }));
+}
+
+#[test]
+fn mirrors_the_observation_after_the_deal() {
+    let mirror = dealt("HA HT CK CQ SJ", "DQ");
+    let observation = mirror.observation().unwrap();
+
+    assert_eq!(observation.hand, cards("HA HT CK CQ SJ"));
+    assert_eq!(observation.playable, observation.hand);
+    assert_eq!(observation.trump, CardSuit::Diamonds);
+    assert_eq!(observation.trump_card, Some(card("DQ")));
+    assert_eq!(observation.deck_size, 9);
+    assert_eq!(observation.opponent_hand_size, 5);
+    assert_eq!(observation.lead, None);
+
+    let legal = mirror.legal_actions();
+    assert_eq!(legal.len(), 6);
+    assert!(legal.contains(&Action::CloseTalon));
+}
+
+#[test]
+fn decides_the_opening_before_the_deal() {
+    let mut mirror = Mirror::new("1");
+    mirror.on_pub_event(&PublicEvent::Active {
+        user_id: "1".to_string(),
+    });
+    mirror.on_priv_event(&PrivateEvent::AllowCuttDeck);
+    assert_eq!(mirror.opening(), Some(Opening::Cut));
+    assert_eq!(mirror.legal_actions().len(), 19);
+    assert!(mirror.observation().is_none());
+
+    mirror.on_pub_event(&PublicEvent::CuttDeck {
+        user_id: "1".to_string(),
+        cards: 4,
+    });
+    mirror.on_priv_event(&PrivateEvent::AllowDrawCard);
+    assert_eq!(
+        mirror.legal_actions(),
+        [Action::TakeCards(3), Action::TakeCards(2)]
+    );
+
+    mirror.on_pub_event(&PublicEvent::TrumpChange(Some(card("DQ"))));
+    assert_eq!(mirror.opening(), None);
+}
+
+#[test]
+fn plays_the_seat_the_server_assigns() {
+    let seat = json!({ "user_id": "1", "timestamp": 3 });
+    let Some(seat) = ServerEvent::parse("seat", seat) else {
+        panic!("expected the seat to parse");
+    };
+
+    let mut mirror = Mirror::new(String::new());
+    mirror.on_event(&seat);
+    assert_eq!(mirror.player_id(), "1");
+
+    mirror.on_pub_event(&PublicEvent::Active {
+        user_id: "1".to_string(),
+    });
+    assert!(mirror.is_my_turn());
+}
+
+#[test]
+fn responds_to_the_opponents_lead() {
+    let mut mirror = dealt("HA HT CK CQ SJ", "DQ");
+    mirror.on_pub_event(&PublicEvent::Active {
+        user_id: "2".to_string(),
+    });
+    assert!(mirror.legal_actions().is_empty());
+
+    play(&mut mirror, "2", "HK");
+    mirror.on_pub_event(&PublicEvent::Active {
+        user_id: "1".to_string(),
+    });
+    let observation = mirror.observation().unwrap();
+    assert_eq!(observation.lead, Some(card("HK")));
+    assert_eq!(observation.opponent_hand_size, 4);
+    // Only cards can be played in response
+    assert_eq!(mirror.legal_actions().len(), 5);
+
+    play(&mut mirror, "1", "HA");
+    mirror.on_pub_event(&PublicEvent::Trick {
+        user_id: "1".to_string(),
+        cards: [card("HK"), card("HA")],
+    });
+    let observation = mirror.observation().unwrap();
+    assert_eq!(observation.won, cards("HK HA"));
+    assert_eq!(observation.points, 15);
+    assert!(mirror.trick().is_empty());
+}
+
+#[test]
+fn announcements_count_once_a_trick_is_won() {
+    let mut mirror = dealt("HA HT CK CQ SJ", "DQ");
+    let marriage = Announcement {
+        cards: [card("CQ"), card("CK")],
+        announce_type: AnnounceType::Twenty,
+    };
+    mirror.on_priv_event(&PrivateEvent::CanAnnounce(marriage.clone()));
+    assert!(mirror
+        .legal_actions()
+        .contains(&Action::Announce20(marriage.cards)));
+
+    mirror.on_pub_event(&PublicEvent::Announce {
+        user_id: "1".to_string(),
+        announcement: marriage,
+    });
+    assert_eq!(mirror.observation().unwrap().points, 0);
+    // Only the announced cards may follow, which the server tells with the playable cards
+    assert!(!mirror.legal_actions().contains(&Action::CloseTalon));
+
+    play(&mut mirror, "1", "CK");
+    play(&mut mirror, "2", "CA");
+    mirror.on_pub_event(&PublicEvent::Trick {
+        user_id: "2".to_string(),
+        cards: [card("CK"), card("CA")],
+    });
+    assert_eq!(mirror.observation().unwrap().points, 0);
+    assert_eq!(mirror.observation().unwrap().opponent_points, 15);
+
+    play(&mut mirror, "2", "HK");
+    play(&mut mirror, "1", "HA");
+    mirror.on_pub_event(&PublicEvent::Trick {
+        user_id: "1".to_string(),
+        cards: [card("HK"), card("HA")],
+    });
+    assert_eq!(mirror.observation().unwrap().points, 35);
+}
+
+#[test]
+fn reconnecting_forgets_the_round_but_not_the_game_points() {
+    let mut mirror = dealt("HA HT CK CQ SJ", "DQ");
+    mirror.on_pub_event(&PublicEvent::Result {
+        winner: "1".to_string(),
+        points: 2,
+        ranked: [("1".to_string(), 2), ("2".to_string(), 0)].into(),
+    });
+    mirror.on_event(&ServerEvent::Connected);
+
+    assert!(mirror.observation().is_none());
+    assert!(mirror.hand().is_empty());
+    assert_eq!(mirror.game_points().get("1"), Some(&2));
+}
@@ -0,0 +1,69 @@
+#!/usr/bin/env bash
+# Runs thousands of matches through a standalone server and prints its memory after every batch.
+# Finished matches are torn down, so after the first batches warmed up the allocator the memory
+# has to stay flat.
+#
+# Usage: ./soak.sh [batches] [matches per batch]
+
+set -euo pipefail
+
+BATCHES=${1:-10}
+BATCH_SIZE=${2:-500}
+ADDR=127.0.0.1:3999
+TOKEN=soak
+# Teardown grace of the match manager, and some slack
+SETTLE=12
+# How much the memory may grow from the second batch to the last, in percent
+MAX_GROWTH=10
+
+cd "$(dirname "$0")"
+cargo build --release --quiet
+
+HOST_ADDR=$ADDR PUBLIC_ADDR=$ADDR PRIVATE_ADDR=$ADDR REGION=local ADMIN_TOKEN=$TOKEN AMQP_URL= \
+    ./target/release/schnapsen-duo-server >/dev/null 2>&1 &
+PID=$!
+trap 'kill $PID' EXIT
+
+until curl -sf "http://$ADDR/" >/dev/null; do
+    sleep 0.2
+done
+
+admin() {
+    curl -sf -H "Authorization: Bearer $TOKEN" "$@"
+}
+
+rss() {
+    awk '/VmRSS/ { print $2 }' "/proc/$PID/status"
+}
+
+echo "batch matches rss_kb"
+echo "0 0 $(rss)"
+for batch in $(seq 1 "$BATCHES"); do
+    for i in $(seq 1 "$BATCH_SIZE"); do
+        read=$(curl -sf -X POST "http://$ADDR/matches" \
+            -H 'Content-Type: application/json' \
+            -d "{\"game\":\"Schnapsen\",\"mode\":\"speed\",\"players\":[\"$batch-$i-a\",\"$batch-$i-b\"],\"ai_players\":[]}" |
+            sed -E 's/.*"read":"([^"]+)".*/\1/')
+        admin -X POST "http://$ADDR/admin/matches/$read/abort" \
+            -H 'Content-Type: application/json' \
+            -d '{"reason":"soak"}'
+    done
+
+    sleep $SETTLE
+    running=$(admin "http://$ADDR/admin/counts" | sed -E 's/.*"matches":([0-9]+).*/\1/')
+    if [ "$running" -ne 0 ]; then
+        echo "$running matches still running after batch $batch" >&2
+        exit 1
+    fi
+
+    rss=$(rss)
+    echo "$batch $((batch * BATCH_SIZE)) $rss"
+    if [ "$batch" -eq 2 ]; then
+        baseline=$rss
+    fi
+done
+
+if [ -n "${baseline:-}" ] && [ "$rss" -gt $((baseline * (100 + MAX_GROWTH) / 100)) ]; then
+    echo "Memory grew from $baseline kB to $rss kB" >&2
+    exit 1
+fi
@@ -0,0 +1,156 @@
+//! Lets ops look into and end the running matches. Every request has to carry the
+//! `ADMIN_TOKEN` as bearer token.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::registry::MatchRegistry;
+
+#[derive(Clone)]
+struct Admin {
+    registry: Arc<MatchRegistry>,
+    token: Arc<str>,
+}
+
+#[derive(Deserialize)]
+struct Abort {
+    reason: String,
+}
+
+#[derive(Serialize)]
+struct IssuedToken {
+    write: String,
+}
+
+/// Compares in constant time, so the token can't be guessed byte by byte.
+fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn authenticate(State(admin): State<Admin>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| token_matches(given, &admin.token));
+
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+async fn list_matches(State(admin): State<Admin>) -> impl IntoResponse {
+    let mut matches: Vec<_> = admin
+        .registry
+        .all()
+        .into_iter()
+        .map(|match_manager| match_manager.summary())
+        .collect();
+    matches.sort_by(|a, b| a.match_id.cmp(&b.match_id));
+    Json(matches)
+}
+
+async fn get_match(State(admin): State<Admin>, Path(match_id): Path<String>) -> Response {
+    match admin.registry.get(&match_id) {
+        Some(match_manager) => Json(match_manager.summary()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn get_event_log(State(admin): State<Admin>, Path(match_id): Path<String>) -> Response {
+    match admin.registry.get(&match_id) {
+        Some(match_manager) => Json(match_manager.get_event_log()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn abort_match(
+    State(admin): State<Admin>,
+    Path(match_id): Path<String>,
+    Json(abort): Json<Abort>,
+) -> StatusCode {
+    let Some(match_manager) = admin.registry.get(&match_id) else {
+        return StatusCode::NOT_FOUND;
+    };
+    info!("Admin aborts match {}: {}", match_id, abort.reason);
+    match_manager.abort(abort.reason);
+    StatusCode::NO_CONTENT
+}
+
+/// Revokes the player's tokens and disconnects them, e.g. if their token leaked.
+async fn remove_player(
+    State(admin): State<Admin>,
+    Path((match_id, player_id)): Path<(String, String)>,
+) -> StatusCode {
+    let Some(match_manager) = admin.registry.get(&match_id) else {
+        return StatusCode::NOT_FOUND;
+    };
+    info!("Admin removes player {} from match {}", player_id, match_id);
+    if match_manager.revoke(&player_id) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+/// A new token for the player, to let them back in after their old ones were revoked.
+async fn issue_token(
+    State(admin): State<Admin>,
+    Path((match_id, player_id)): Path<(String, String)>,
+) -> Response {
+    let write = admin
+        .registry
+        .get(&match_id)
+        .and_then(|match_manager| match_manager.issue_token(&player_id));
+    match write {
+        Some(write) => (StatusCode::CREATED, Json(IssuedToken { write })).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn counts(State(admin): State<Admin>) -> impl IntoResponse {
+    Json(admin.registry.counts())
+}
+
+pub fn router(registry: Arc<MatchRegistry>, token: &str) -> Router {
+    let admin = Admin {
+        registry,
+        token: token.into(),
+    };
+
+    Router::new()
+        .route("/admin/matches", routing::get(list_matches))
+        .route("/admin/matches/:match_id", routing::get(get_match))
+        .route(
+            "/admin/matches/:match_id/events",
+            routing::get(get_event_log),
+        )
+        .route("/admin/matches/:match_id/abort", routing::post(abort_match))
+        .route(
+            "/admin/matches/:match_id/players/:player_id",
+            routing::delete(remove_player),
+        )
+        .route(
+            "/admin/matches/:match_id/players/:player_id/token",
+            routing::post(issue_token),
+        )
+        .route("/admin/counts", routing::get(counts))
+        .route_layer(middleware::from_fn_with_state(admin.clone(), authenticate))
+        .with_state(admin)
+}
@@ -0,0 +1,55 @@
+use futures::{future::BoxFuture, FutureExt};
+use gn_communicator::{
+    models::{CreateMatch, CreatedMatch, GameServerCreate, MatchAbrubtClose, MatchResult},
+    rabbitmq::RabbitMQCommunicator,
+    Communicator as _,
+};
+use tracing::{error, info};
+
+use super::{Communicator, CreateHandler};
+
+/// Talks to the matchmaker via RabbitMQ.
+pub struct AmqpCommunicator(RabbitMQCommunicator);
+
+impl AmqpCommunicator {
+    pub async fn connect(amqp_url: &str) -> Self {
+        info!("Connecting to the matchmaker via RabbitMQ");
+        Self(RabbitMQCommunicator::connect(amqp_url).await)
+    }
+}
+
+impl Communicator for AmqpCommunicator {
+    fn on_match_create(&self, handler: CreateHandler) -> BoxFuture<'_, ()> {
+        async move {
+            self.0
+                .on_match_create(move |new_match: CreateMatch| handler(new_match))
+                .await
+        }
+        .boxed()
+    }
+
+    fn register_game(&self, game_server: GameServerCreate) -> BoxFuture<'_, ()> {
+        async move {
+            if let Err(err) = self.0.create_game(&game_server).await {
+                error!("Could not register mode {}: {:?}", game_server.mode, err);
+            }
+        }
+        .boxed()
+    }
+
+    fn send_health_check(&self, id: String) -> BoxFuture<'_, ()> {
+        self.0.send_health_check(id).boxed()
+    }
+
+    fn report_match_created(&self, created_match: CreatedMatch) -> BoxFuture<'_, ()> {
+        async move { self.0.report_match_created(&created_match).await }.boxed()
+    }
+
+    fn report_match_result(&self, result: MatchResult) -> BoxFuture<'_, ()> {
+        async move { self.0.report_match_result(&result).await }.boxed()
+    }
+
+    fn report_match_abrupt_close(&self, reason: MatchAbrubtClose) -> BoxFuture<'_, ()> {
+        async move { self.0.report_match_abrupt_close(&reason).await }.boxed()
+    }
+}
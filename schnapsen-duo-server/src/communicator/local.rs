@@ -0,0 +1,50 @@
+use futures::{future::BoxFuture, FutureExt};
+use gn_communicator::models::{CreatedMatch, GameServerCreate, MatchAbrubtClose, MatchResult};
+use tracing::{debug, info};
+
+use super::{Communicator, CreateHandler};
+
+/// Stands in for the matchmaker if the server runs on its own, e.g. locally or at a LAN event.
+/// Matches are then created over HTTP, and their results are only logged.
+pub struct LocalCommunicator;
+
+impl LocalCommunicator {
+    pub fn new() -> Self {
+        info!("No matchmaker configured, running standalone");
+        Self
+    }
+}
+
+impl Communicator for LocalCommunicator {
+    fn on_match_create(&self, _handler: CreateHandler) -> BoxFuture<'_, ()> {
+        debug!("Without a matchmaker, matches are only created over HTTP");
+        async {}.boxed()
+    }
+
+    fn register_game(&self, game_server: GameServerCreate) -> BoxFuture<'_, ()> {
+        debug!("Offering mode: {:?}", game_server.mode);
+        async {}.boxed()
+    }
+
+    fn send_health_check(&self, _id: String) -> BoxFuture<'_, ()> {
+        async {}.boxed()
+    }
+
+    fn report_match_created(&self, created_match: CreatedMatch) -> BoxFuture<'_, ()> {
+        info!("Match created: {:?}", created_match);
+        async {}.boxed()
+    }
+
+    fn report_match_result(&self, result: MatchResult) -> BoxFuture<'_, ()> {
+        info!(
+            "Match {} won by {:?} against {:?}",
+            result.match_id, result.winners, result.losers
+        );
+        async {}.boxed()
+    }
+
+    fn report_match_abrupt_close(&self, reason: MatchAbrubtClose) -> BoxFuture<'_, ()> {
+        info!("Match closed: {:?}", reason);
+        async {}.boxed()
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use gn_communicator::models::{
+    CreateMatch, CreatedMatch, GameServerCreate, MatchAbrubtClose, MatchResult,
+};
+
+mod amqp;
+mod local;
+
+pub use amqp::AmqpCommunicator;
+pub use local::LocalCommunicator;
+
+/// Handles a request of the matchmaker to create a match.
+pub type CreateHandler = Arc<dyn Fn(CreateMatch) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// What the server needs of the matchmaker: match requests in, the matches and their results
+/// out.
+pub trait Communicator: Send + Sync {
+    fn on_match_create(&self, handler: CreateHandler) -> BoxFuture<'_, ()>;
+
+    fn register_game(&self, game_server: GameServerCreate) -> BoxFuture<'_, ()>;
+
+    fn send_health_check(&self, id: String) -> BoxFuture<'_, ()>;
+
+    fn report_match_created(&self, created_match: CreatedMatch) -> BoxFuture<'_, ()>;
+
+    fn report_match_result(&self, result: MatchResult) -> BoxFuture<'_, ()>;
+
+    fn report_match_abrupt_close(&self, reason: MatchAbrubtClose) -> BoxFuture<'_, ()>;
+}
+
+/// Where to reach the matchmaker, if the server doesn't run on its own.
+pub fn amqp_url() -> Option<String> {
+    std::env::var("AMQP_URL")
+        .ok()
+        .or(option_env!("AMQP_URL").map(String::from))
+        .filter(|amqp_url| !amqp_url.is_empty())
+}
+
+pub async fn connect(amqp_url: Option<String>) -> Arc<dyn Communicator> {
+    match amqp_url {
+        Some(amqp_url) => Arc::new(AmqpCommunicator::connect(&amqp_url).await),
+        None => Arc::new(LocalCommunicator::new()),
+    }
+}
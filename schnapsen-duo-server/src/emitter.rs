@@ -1,4 +1,4 @@
-use schnapsen_rs::{PrivateEvent, PublicEvent};
+use schnapsen_rs::{models::Named, PrivateEvent, PublicEvent};
 use serde::Serialize;
 use socketioxide::{extract::SocketRef, operators::BroadcastOperators};
 use tracing::debug;
@@ -7,6 +7,13 @@ pub trait EventIdentifier {
     fn event_name(&self) -> &'static str;
 }
 
+/// The event is emitted under its own name, only its cards are named differently.
+impl<T: EventIdentifier> EventIdentifier for Named<'_, T> {
+    fn event_name(&self) -> &'static str {
+        self.inner.event_name()
+    }
+}
+
 pub fn to_private_event_emitter<'a, T>(
     event: &'a T,
 ) -> impl Fn(SocketRef) -> Result<(), socketioxide::SendError<T>> + 'a
@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+
 use event_logger::{EventLike};
-use schnapsen_rs::PrivateEvent;
+use schnapsen_rs::{models::Action, PrivateEvent};
 use serde::Serialize;
 
-use crate::emitter::EventIdentifier;
+use crate::{
+    emitter::EventIdentifier,
+    models::{Decision, Flag},
+};
 
 
 pub mod event_logger;
@@ -13,6 +18,7 @@ pub mod event_logger;
 pub enum EventType<Prv, Pub> {
     Private(Prv),
     Public(Pub),
+    Server(ServerEvent),
 }
 
 impl<Prv, Pub> EventLike for EventType<Prv, Pub>
@@ -31,6 +37,7 @@ where
         match self {
             EventType::Private(event) => event.event_name(),
             EventType::Public(event) => event.event_name(),
+            EventType::Server(event) => event.event_name(),
         }
     }
 }
@@ -38,6 +45,42 @@ where
 impl EventLike for schnapsen_rs::PrivateEvent {}
 impl EventLike for schnapsen_rs::PublicEvent {}
 
+/// Public events of the server itself, which the engine knows nothing about.
+#[derive(Serialize, Hash, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "event", content = "data")]
+pub enum ServerEvent {
+    /// A bot plays for the disconnected player until they reconnect.
+    Takeover { user_id: String },
+    /// The player reconnected and plays on their own again.
+    Handback { user_id: String },
+    /// A move the server made on behalf of the player.
+    AutomatedMove { user_id: String, action: Action },
+    /// The time banks in milliseconds, and whose clock is running.
+    Clock {
+        active: Option<String>,
+        remaining: BTreeMap<String, u64>,
+    },
+    /// The player ran out of time.
+    Flagged { user_id: String, lost: Flag },
+    /// An admin ended the match.
+    Aborted { reason: String },
+}
+
+impl EventLike for ServerEvent {}
+
+impl EventIdentifier for ServerEvent {
+    fn event_name(&self) -> &'static str {
+        match self {
+            ServerEvent::Takeover { .. } => "takeover",
+            ServerEvent::Handback { .. } => "handback",
+            ServerEvent::AutomatedMove { .. } => "automated_move",
+            ServerEvent::Clock { .. } => "clock",
+            ServerEvent::Flagged { .. } => "flagged",
+            ServerEvent::Aborted { .. } => "aborted",
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct TimedEvent<T>
 where
@@ -51,6 +94,20 @@ where
 #[derive(Debug, Clone, Serialize)]
 pub struct TimeoutThreat {
     pub timeout: u64,
+    pub decision: Decision,
+}
+
+/// The player has only `remaining` seconds left for their decision.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeoutWarning {
+    pub remaining: u64,
+    pub decision: Decision,
+}
+
+/// Tells the socket which player its write token is for, as the events only name the players.
+#[derive(Debug, Clone, Serialize)]
+pub struct Seat {
+    pub user_id: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -76,6 +133,18 @@ impl EventIdentifier for TimeoutThreat {
     }
 }
 
+impl EventIdentifier for TimeoutWarning {
+    fn event_name(&self) -> &'static str {
+        "timeout_warning"
+    }
+}
+
+impl EventIdentifier for Seat {
+    fn event_name(&self) -> &'static str {
+        "seat"
+    }
+}
+
 impl<T> EventIdentifier for TimedEvent<T>
 where
     T: EventIdentifier + Clone + Serialize,
@@ -113,6 +182,7 @@ impl EventIdentifier for schnapsen_rs::PrivateEvent {
             schnapsen_rs::PrivateEvent::CannotAnnounce(_) => "cannot_announce",
             schnapsen_rs::PrivateEvent::TrumpChangeImpossible(_) => "trump_change_impossible",
             schnapsen_rs::PrivateEvent::AllowSwapTrump => "allow_swap_trump",
+            schnapsen_rs::PrivateEvent::AllowCuttDeck => "allow_cutt_deck",
         }
     }
 }
@@ -132,6 +202,7 @@ impl EventIdentifier for schnapsen_rs::PublicEvent {
             schnapsen_rs::PublicEvent::TrumpChange { .. } => "trump_change",
             schnapsen_rs::PublicEvent::Result { .. } => "result",
             schnapsen_rs::PublicEvent::FinishedDistribution { .. } => "finished_distribution",
+            schnapsen_rs::PublicEvent::CuttDeck { .. } => "cutt_deck",
             schnapsen_rs::PublicEvent::Score { .. } => "score",
         }
     }
@@ -1,8 +1,5 @@
-use async_once::AsyncOnce;
-use axum::{self, routing};
-use futures::future::join_all;
-use gn_communicator::{rabbitmq::RabbitMQCommunicator, Communicator};
-use lazy_static::lazy_static;
+use axum::{self, http::StatusCode, routing, Json};
+use futures::{future::join_all, FutureExt};
 use socketioxide::SocketIo;
 use std::sync::Arc;
 use tokio::join;
@@ -11,82 +8,135 @@ use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
-use tracing::{debug, info, Level};
+use tracing::{debug, error, info, Level};
 use tracing_subscriber::FmtSubscriber;
 
+use communicator::Communicator;
+use models::MatchCreated;
+use modes::Modes;
+use registry::MatchRegistry;
+use tokens::Tokens;
+
+mod admin;
+mod communicator;
 mod emitter;
 mod events;
 mod match_manager;
 mod models;
+mod modes;
 mod performer;
+mod registry;
+mod tokens;
 mod translator;
+#[cfg(test)]
+mod tests;
 
-lazy_static! {
-    static ref communicator: AsyncOnce<RabbitMQCommunicator> = AsyncOnce::new(
-        RabbitMQCommunicator::connect(option_env!("AMQP_URL").unwrap())
-    );
-}
-
-async fn notify_match_close(reason: gn_communicator::models::MatchAbrubtClose) {
-    debug!("Notifying match result: {:?}", reason);
-    communicator
-        .get()
-        .await
-        .report_match_abrupt_close(&reason)
-        .await;
-}
-
-async fn notify_match_result(result: gn_communicator::models::MatchResult) {
-    debug!("Notifying match result: {:?}", result);
-    communicator.get().await.report_match_result(&result).await;
-}
-
-fn setup_match_result_handler(match_manager: Arc<match_manager::WriteMatchManager>) {
+fn setup_match_result_handler(
+    communicator: Arc<dyn Communicator>,
+    match_manager: Arc<match_manager::WriteMatchManager>,
+) {
     match_manager.on_exit(move |event| {
         async_std::task::spawn(async move {
             if let Ok(result) = event {
-                notify_match_result(result.into()).await;
+                debug!("Notifying match result: {:?}", result);
+                communicator.report_match_result(result.into()).await;
             } else if let Err(reason) = event {
-                notify_match_close(reason.into()).await;
+                debug!("Notifying match close: {:?}", reason);
+                communicator.report_match_abrupt_close(reason.into()).await;
             }
         });
     });
 }
 
-async fn listen_for_match_create(io: Arc<SocketIo>) {
+/// What it takes to host matches.
+#[derive(Clone)]
+struct Host {
+    io: Arc<SocketIo>,
+    modes: Arc<Modes>,
+    communicator: Arc<dyn Communicator>,
+    registry: Arc<MatchRegistry>,
+    tokens: Arc<Tokens>,
+}
+
+/// Creates the match and reports its result once it's over. Fails if the server can't host it.
+fn create_match(
+    host: &Host,
+    new_match: gn_communicator::models::CreateMatch,
+) -> Result<MatchCreated, String> {
+    let Some(mode) = host.modes.get(&new_match.mode) else {
+        return Err(format!("Unknown mode: {:?}", new_match.mode));
+    };
+    if new_match.players.len() != 2 {
+        return Err(format!(
+            "Schnapsen Duo needs 2 players, not {}",
+            new_match.players.len()
+        ));
+    }
+
+    let match_manager = host.registry.create(|match_id| {
+        match_manager::WriteMatchManager::create(
+            host.io.clone(),
+            match_id,
+            new_match,
+            mode,
+            2,
+            host.tokens.clone(),
+        )
+    });
+    let created_match = match_manager.get_meta();
+    setup_match_result_handler(host.communicator.clone(), match_manager);
+    Ok(created_match)
+}
+
+async fn listen_for_match_create(host: Host) {
     info!("Listening for match create requests");
+    let communicator = host.communicator.clone();
     let on_create = move |new_match: gn_communicator::models::CreateMatch| {
-        match_manager::WriteMatchManager::create(io.clone(), new_match, 2)
+        let created_match = create_match(&host, new_match);
+        let communicator = host.communicator.clone();
+        async move {
+            match created_match {
+                Ok(created_match) => {
+                    communicator
+                        .report_match_created(created_match.into())
+                        .await
+                }
+                Err(err) => error!("Can't create match: {}", err),
+            }
+        }
+        .boxed()
     };
 
-    communicator
-        .get()
-        .await
-        .on_match_create(move |new_match: gn_communicator::models::CreateMatch| {
-            let on_create = on_create.clone();
-            async move {
-                let match_manager = on_create(new_match.clone());
-                let created_match = match_manager.get_meta().clone();
-
-                setup_match_result_handler(match_manager);
-
-                communicator
-                    .get()
-                    .await
-                    .report_match_created(&created_match.into())
-                    .await;
-            }
-        })
-        .await;
+    communicator.on_match_create(Arc::new(on_create)).await;
 }
 
-async fn register_server(modes: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+/// Creates a match without the matchmaker, for servers running on their own. The players
+/// receive the write tokens in the response.
+async fn create_match_directly(
+    host: Host,
+    new_match: gn_communicator::models::CreateMatch,
+) -> Result<(StatusCode, Json<MatchCreated>), (StatusCode, String)> {
+    match create_match(&host, new_match) {
+        Ok(created_match) => {
+            host.communicator
+                .report_match_created(created_match.clone().into())
+                .await;
+            Ok((StatusCode::CREATED, Json(created_match)))
+        }
+        Err(err) => Err((StatusCode::BAD_REQUEST, err)),
+    }
+}
+
+async fn register_server(
+    communicator: &Arc<dyn Communicator>,
+    modes: &Modes,
+) -> Result<(), Box<dyn std::error::Error>> {
     let public_url = std::env::var("PUBLIC_ADDR").expect("SCHNAPSEN_DUO_PUBLIC_ADDR must be set");
     let private_url =
         std::env::var("PRIVATE_ADDR").expect("SCHNAPSEN_DUO_PRIVATE_ADDR must be set");
     let region = std::env::var("REGION").expect("REGION must be set");
 
-    join_all(modes.into_iter().map(|mode| {
+    join_all(modes.iter().map(|(mode, config)| {
         let server_info = gn_communicator::models::GameServerCreate {
             region: region.clone(),
             game: "Schnapsen".to_string(),
@@ -95,39 +145,23 @@ async fn register_server(modes: &[String]) -> Result<(), Box<dyn std::error::Err
             server_priv: private_url.clone(),
             max_players: 2,
             min_players: 2,
-            ranking_conf: gn_communicator::models::RankingConf {
-                max_stars: 50,
-                description: "Schnapsen Duo".to_string(),
-                performances: vec![],
-            },
+            ranking_conf: config.ranking.clone(),
         };
 
-        tokio::spawn(async move {
-            communicator
-                .get()
-                .await
-                .create_game(&server_info)
-                .await
-                .unwrap()
-        })
+        let communicator = communicator.clone();
+        tokio::spawn(async move { communicator.register_game(server_info).await })
     })).await;
     Ok(())
 }
 
 
-async fn health_check(id: String) {
-    communicator.get().await.send_health_check(id).await;
-}
-
-async fn run_health_check(id: String) -> ! {
+async fn run_health_check(id: String, modes: Arc<Modes>, communicator: Arc<dyn Communicator>) -> ! {
     let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
     loop {
         debug!("Waiting for send health-check");
         interval.tick().await;
-        health_check(id.clone()).await;
-        let server_ids = register_server(&["speed".to_string(), "bummerl".to_string()])
-            .await
-            .unwrap();
+        communicator.send_health_check(id.clone()).await;
+        let server_ids = register_server(&communicator, &modes).await.unwrap();
         info!("Registered servers as {:?}", server_ids);
 debug!("Sent health-check");
     }
@@ -150,18 +184,35 @@ async fn main() {
     let (layer, io) = socketioxide::SocketIo::new_layer();
     let io = Arc::new(io);
 
-    tokio::spawn(listen_for_match_create(io));
+    let modes = Arc::new(Modes::load());
+    let amqp_url = communicator::amqp_url();
+    let standalone = amqp_url.is_none();
+    let communicator = communicator::connect(amqp_url).await;
+
+    let host = Host {
+        io,
+        modes: modes.clone(),
+        communicator: communicator.clone(),
+        registry: Arc::new(MatchRegistry::new()),
+        tokens: Arc::new(Tokens::from_env()),
+    };
+
+    tokio::spawn(listen_for_match_create(host.clone()));
 
     let private_url =
         std::env::var("PRIVATE_ADDR").expect("SCHNAPSEN_DUO_PRIVATE_ADDR must be set");
-    tokio::spawn(run_health_check(private_url));
+    tokio::spawn(run_health_check(
+        private_url,
+        modes.clone(),
+        communicator.clone(),
+    ));
 
     let host_url = std::env::var("HOST_ADDR").expect("HOST_ADDR must be set");
     let listener = tokio::net::TcpListener::bind(host_url.as_str())
         .await
         .unwrap();
 
-    let router = axum::Router::new()
+    let mut router = axum::Router::new()
         .layer(layer)
         .layer(CorsLayer::new().allow_origin(Any))
         .layer(
@@ -171,6 +222,21 @@ async fn main() {
         )
         .route("/", routing::get(|| async {}));
 
+    if standalone {
+        let host = host.clone();
+        router = router.route(
+            "/matches",
+            routing::post(move |Json(new_match)| create_match_directly(host.clone(), new_match)),
+        );
+    }
+
+    match std::env::var("ADMIN_TOKEN") {
+        Ok(token) if !token.is_empty() => {
+            router = router.merge(admin::router(host.registry.clone(), &token));
+        }
+        _ => info!("No ADMIN_TOKEN set, the admin API is disabled"),
+    }
+
     info!("Listening on {}", host_url);
     axum::serve(listener, router).await.unwrap();
 }
@@ -0,0 +1,164 @@
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+
+use rand::{seq::SliceRandom, Rng};
+use schnapsen_rs::{
+    agent::{Agent, Persona, PERSONAS},
+    client::SchnapsenDuoClient,
+    models::Action,
+};
+use tokio::sync::watch;
+use tracing::{debug, error, warn};
+
+use super::WriteMatchManager;
+use crate::events::ServerEvent;
+
+/// Plays for players who disconnected.
+pub const STAND_IN: Persona = Persona {
+    display_name: "Stand-in",
+    elo: 0,
+    agent: "greedy",
+    think: Duration::from_millis(800),
+};
+
+/// A seat of the match which is played by an agent directly on the engine, instead of a client
+/// connecting like a human.
+pub struct AiPlayer {
+    persona: &'static Persona,
+    player_id: String,
+    client: SchnapsenDuoClient,
+    /// Whether the agent stands in for a human, whose moves are then logged as automated.
+    stand_in: bool,
+}
+
+impl AiPlayer {
+    fn new(
+        match_manager: &WriteMatchManager,
+        player_id: &str,
+        persona: &'static Persona,
+        stand_in: bool,
+    ) -> Option<Self> {
+        let player = match_manager
+            .instance
+            .lock()
+            .unwrap()
+            .get_player(player_id)?;
+
+        Some(Self {
+            persona,
+            player_id: player_id.to_string(),
+            client: SchnapsenDuoClient::new(player, match_manager.instance.clone()),
+            stand_in,
+        })
+    }
+
+    /// An AI seat, played by the persona of the same name.
+    pub fn seat(match_manager: &WriteMatchManager, player_id: &str) -> Option<Self> {
+        let persona = Persona::find(player_id).unwrap_or_else(|| {
+            warn!(
+                "Unknown persona {:?}, playing as {:?}",
+                player_id, PERSONAS[0].display_name
+            );
+            &PERSONAS[0]
+        });
+        Self::new(match_manager, player_id, persona, false)
+    }
+
+    /// Plays for a human until they are back.
+    pub fn stand_in(match_manager: &WriteMatchManager, player_id: &str) -> Option<Self> {
+        Self::new(match_manager, player_id, &STAND_IN, true)
+    }
+
+    /// Waits a bit longer or shorter than the persona usually thinks, like a human would.
+    fn think(&self) -> Duration {
+        self.persona
+            .think
+            .mul_f64(rand::thread_rng().gen_range(0.7..1.3))
+    }
+
+    fn is_playing(&self, match_manager: &WriteMatchManager) -> bool {
+        if match_manager.exited.load(Ordering::SeqCst) > 0 {
+            return false;
+        }
+        !self.stand_in
+            || match_manager
+                .takeovers
+                .lock()
+                .unwrap()
+                .contains(&self.player_id)
+    }
+
+    /// Decides on one of the legal actions without blocking the runtime, as a search may take a
+    /// while. `None` if it isn't the AI's turn.
+    async fn decide(
+        &self,
+        mut agent: Box<dyn Agent>,
+    ) -> (Box<dyn Agent>, Option<(Action, Vec<Action>)>) {
+        let legal = self.client.legal_actions();
+        let observation = match self.client.observe() {
+            Some(observation) if !legal.is_empty() => observation,
+            // Cutting and taking up the cards come before the deal, with nothing to think about
+            None if !legal.is_empty() => {
+                let action = *legal.choose(&mut rand::thread_rng()).unwrap();
+                return (agent, Some((action, legal)));
+            }
+            _ => return (agent, None),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let action = agent.act(&observation, &legal);
+            (agent, Some((action, legal)))
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Plays whenever it's the AI's turn, until the match is over or the human is back. `turn`
+    /// changes with every event of the match.
+    pub async fn play(self, match_manager: Arc<WriteMatchManager>, mut turn: watch::Receiver<()>) {
+        let mut agent = self.persona.agent(rand::random());
+
+        while turn.changed().await.is_ok() && self.is_playing(&match_manager) {
+            while !self.client.legal_actions().is_empty() {
+                async_std::task::sleep(self.think()).await;
+                if !self.is_playing(&match_manager) {
+                    return;
+                }
+
+                let (returned, decision) = self.decide(agent).await;
+                agent = returned;
+                let Some((action, legal)) = decision else {
+                    break;
+                };
+                debug!("{} performs {:?}", self.persona.display_name, action);
+
+                // Should the engine disagree with the agent, the seat must not get stuck
+                let performed = std::iter::once(action)
+                    .chain(legal)
+                    .find(|action| match self.client.perform(*action) {
+                        Ok(()) => true,
+                        Err(err) => {
+                            error!("AI could not perform {:?}: {:?}", action, err);
+                            false
+                        }
+                    });
+                let Some(action) = performed else {
+                    break;
+                };
+                if self.stand_in {
+                    match_manager.emit_server_event(ServerEvent::AutomatedMove {
+                        user_id: self.player_id.clone(),
+                        action,
+                    });
+                }
+            }
+        }
+
+        debug!(
+            "{} left {:?} in match {}",
+            self.persona.display_name, self.player_id, match_manager.match_id
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+use crate::{events::ServerEvent, models::Flag, modes::secs};
+
+/// A time bank per player, plus an increment for every move they make.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    #[serde(deserialize_with = "secs")]
+    pub bank: Duration,
+    #[serde(deserialize_with = "secs")]
+    pub increment: Duration,
+    pub flag: Flag,
+}
+
+/// The clocks of the players of a match, of which at most one runs at a time.
+pub struct Clock {
+    control: TimeControl,
+    /// The time left of each player, not counting the running clock.
+    banks: HashMap<String, Duration>,
+    running: Option<(String, Instant)>,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl, players: &[String]) -> Self {
+        Self {
+            control,
+            banks: players
+                .iter()
+                .map(|player_id| (player_id.clone(), control.bank))
+                .collect(),
+            running: None,
+        }
+    }
+
+    #[inline]
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Starts the clock of the player, stopping the one running before.
+    pub fn start(&mut self, player_id: &str) {
+        self.stop();
+        self.running = Some((player_id.to_string(), Instant::now()));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some((player_id, since)) = self.running.take() {
+            if let Some(bank) = self.banks.get_mut(&player_id) {
+                *bank = bank.saturating_sub(since.elapsed());
+            }
+        }
+    }
+
+    /// Credits the increment for a move of the player.
+    pub fn on_move(&mut self, player_id: &str) {
+        if let Some(bank) = self.banks.get_mut(player_id) {
+            *bank += self.control.increment;
+        }
+    }
+
+    /// Fills up the banks for a new round.
+    pub fn refill(&mut self) {
+        self.stop();
+        self.banks
+            .values_mut()
+            .for_each(|bank| *bank = self.control.bank);
+    }
+
+    /// The time the player has left, counting their clock if it's running.
+    pub fn remaining(&self, player_id: &str) -> Duration {
+        let bank = self.banks.get(player_id).copied().unwrap_or_default();
+        match &self.running {
+            Some((running, since)) if running == player_id => bank.saturating_sub(since.elapsed()),
+            _ => bank,
+        }
+    }
+
+    pub fn state(&self) -> ServerEvent {
+        ServerEvent::Clock {
+            active: self
+                .running
+                .as_ref()
+                .map(|(player_id, _)| player_id.clone()),
+            remaining: self
+                .banks
+                .keys()
+                .map(|player_id| {
+                    (
+                        player_id.clone(),
+                        self.remaining(player_id).as_millis() as u64,
+                    )
+                })
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+}
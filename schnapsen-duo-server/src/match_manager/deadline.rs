@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use schnapsen_rs::{PrivateEvent, PublicEvent};
+use serde::Deserialize;
+
+use crate::{
+    models::Decision,
+    modes::{secs, secs_list},
+};
+
+impl Decision {
+    /// The decision the engine asks for with the event, if it has to be made in time.
+    pub fn of(event: &PrivateEvent) -> Option<Self> {
+        match event {
+            PrivateEvent::AllowPlayCard => Some(Decision::PlayCard),
+            PrivateEvent::AllowCuttDeck => Some(Decision::Cut),
+            PrivateEvent::AllowDrawCard => Some(Decision::TakeCards),
+            _ => None,
+        }
+    }
+
+    /// Whether the event shows that the player made the decision.
+    pub fn is_made_by(&self, event: &PublicEvent, player_id: &str) -> bool {
+        match (self, event) {
+            (Decision::PlayCard, PublicEvent::PlayCard { user_id, .. }) => user_id == player_id,
+            (Decision::Cut, PublicEvent::CuttDeck { user_id, .. }) => user_id == player_id,
+            (Decision::TakeCards, PublicEvent::ReceiveCard { user_id }) => user_id == player_id,
+            _ => false,
+        }
+    }
+}
+
+/// How long players have for their decisions, and how long before the deadline they are warned.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Deadlines {
+    #[serde(deserialize_with = "secs")]
+    play_card: Duration,
+    #[serde(deserialize_with = "secs")]
+    cut: Duration,
+    #[serde(deserialize_with = "secs")]
+    take_cards: Duration,
+    /// The time left at which the players are warned.
+    #[serde(deserialize_with = "secs_list")]
+    warnings: Vec<Duration>,
+}
+
+impl Default for Deadlines {
+    fn default() -> Self {
+        Self {
+            play_card: Duration::from_secs(30),
+            cut: Duration::from_secs(15),
+            take_cards: Duration::from_secs(15),
+            warnings: vec![Duration::from_secs(10), Duration::from_secs(5)],
+        }
+    }
+}
+
+impl Deadlines {
+    pub fn timeout(&self, decision: Decision) -> Duration {
+        match decision {
+            Decision::PlayCard => self.play_card,
+            Decision::Cut => self.cut,
+            Decision::TakeCards => self.take_cards,
+        }
+    }
+
+    /// The warnings due before `timeout` runs out, the earliest first.
+    pub fn warnings(&self, timeout: Duration) -> Vec<Duration> {
+        let mut warnings: Vec<_> = self
+            .warnings
+            .iter()
+            .copied()
+            .filter(|remaining| *remaining < timeout)
+            .collect();
+        warnings.sort_by(|a, b| b.cmp(a));
+        warnings
+    }
+}
@@ -1,16 +1,20 @@
 use core::time;
 use std::{
-    collections::HashMap,
-    hash::{Hash, Hasher},
+    collections::{HashMap, HashSet},
     sync::{
         atomic::{AtomicBool, AtomicI8, AtomicU64},
         Arc, RwLock,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use futures::{lock::Mutex, task, FutureExt};
-use schnapsen_rs::{PrivateEvent, PublicEvent, SchnapsenDuo};
+use schnapsen_rs::{
+    agent::{Agent, GreedyAgent},
+    client::SchnapsenDuoClient,
+    models::{Action, Named, Naming},
+    Opening, PrivateEvent, PublicEvent, SchnapsenDuo,
+};
 use serde::Serialize;
 use socketioxide::{
     extract::{Data, SocketRef},
@@ -27,17 +31,33 @@ use tracing::error;
 use crate::{
     emitter,
     events::{
-        event_logger, EventType, SchnapsenDuoEventType, TimedEvent, TimeoutThreat,
-        TimeoutThreatClose,
+        event_logger, EventType, SchnapsenDuoEventType, Seat, ServerEvent, TimedEvent,
+        TimeoutThreat, TimeoutThreatClose, TimeoutWarning,
     },
     match_manager,
     models::{
-        CreateMatch, MatchAbruptClose, MatchCreated, MatchError, MatchResult, Ranking, Timeout,
+        CreateMatch, Decision, Flag, MatchAbruptClose, MatchCreated, MatchError, MatchResult,
+        MatchSummary, Phase, PlayerSummary, Timeout,
     },
-    performer, translator,
+    modes::{Length, ModeConfig, Variant},
+    performer, tokens::Tokens, translator,
 };
+
+mod ai_player;
+mod clock;
+mod deadline;
+mod performances;
+
+use clock::Clock;
+pub use clock::TimeControl;
+pub use deadline::Deadlines;
+pub use performances::{Performances, KNOWN as PERFORMANCES};
+
 const PUBLIC_EVENT_ROOM: &str = "public-events";
-const FORCE_MOVE_TIMEOUT: u64 = 30;
+/// How long players have to join or reconnect, in seconds.
+const CONNECT_TIMEOUT: u64 = 30;
+/// How long the players stay connected to a finished match to see how it ended, in seconds.
+pub const TEARDOWN_GRACE: u64 = 10;
 
 pub struct WriteMatchManager {
     instance: Arc<std::sync::Mutex<SchnapsenDuo>>,
@@ -52,26 +72,56 @@ pub struct WriteMatchManager {
         std::sync::Mutex<Vec<Box<dyn FnOnce(Result<MatchResult, MatchAbruptClose>) + Send + Sync>>>,
     min_players: usize,
     bummerl: bool,
+    /// How long the players see the result of a round of the bummerl before the next is dealt.
+    next_round_delay: Duration,
+    performances: std::sync::Mutex<Performances>,
     round_begin_timestamp: AtomicU64,
+    io: Arc<SocketIo>,
+    /// Whether a bot plays for disconnected players, instead of them timing out.
+    bot_takeover: bool,
+    /// The players a bot currently plays for.
+    takeovers: std::sync::Mutex<HashSet<String>>,
+    /// How often in a row the server may move for a player who ran out of time, before they
+    /// forfeit.
+    max_auto_moves: u32,
+    /// How often the server moved for the players since they last moved on their own.
+    auto_moves: std::sync::Mutex<HashMap<String, u32>>,
+    /// Changed with every event a player sees, to wake up the bots playing for them.
+    turns: std::sync::Mutex<HashMap<String, Arc<Sender<()>>>>,
+    /// Replaces the fixed timeout per move, if the mode is played with a time control.
+    clock: Option<std::sync::Mutex<Clock>>,
+    deadlines: Deadlines,
+    /// The names the cards in the events are sent with.
+    naming: Naming,
+    /// How many decisions each player was handed, a deadline only runs out on the latest one.
+    decisions: std::sync::Mutex<HashMap<String, u64>>,
+    tokens: Arc<Tokens>,
+    /// The generation of write tokens each player has to show, earlier ones were revoked.
+    token_generations: std::sync::Mutex<HashMap<String, u32>>,
 }
 
 impl WriteMatchManager {
+    /// Hosts the match under `match_id`, which is also the token to watch it.
     pub fn create(
         io: Arc<SocketIo>,
+        match_id: String,
         new_match: gn_communicator::models::CreateMatch,
+        mode: &ModeConfig,
         min_players: usize,
+        tokens: Arc<Tokens>,
     ) -> Arc<Self> {
         debug!("Creating new match: {:?}", new_match);
-        let write = new_match.players.clone();
+        // Everyone in the match sees the player IDs, so the tokens to play a seat are signed apart
+        let write: Vec<_> = new_match
+            .players
+            .iter()
+            .map(|player_id| tokens.issue(&match_id, player_id, 0))
+            .collect();
 
         let io = io.clone();
-        let instance = Arc::new(std::sync::Mutex::new(SchnapsenDuo::new(
-            new_match.players.as_slice().try_into().unwrap(),
-        )));
-
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        instance.lock().unwrap().hash(&mut hasher);
-        let read = hasher.finish();
+        let instance = Arc::new(std::sync::Mutex::new(match mode.variant {
+            Variant::Duo => SchnapsenDuo::new(new_match.players.as_slice().try_into().unwrap()),
+        }));
 
         let public_url =
             std::env::var("PUBLIC_ADDR").expect("SCHNAPSEN_DUO_PUBLIC_ADDR must be set");
@@ -81,6 +131,18 @@ impl WriteMatchManager {
 
         let logger = Self::setup_event_log(instance.clone(), &new_match);
 
+        // AI seats have to be seats of the match
+        let ai_players = new_match
+            .ai_players
+            .iter()
+            .filter(|player_id| new_match.players.contains(player_id))
+            .cloned()
+            .collect();
+
+        let clock = mode
+            .clock
+            .map(|control| std::sync::Mutex::new(Clock::new(control, &new_match.players)));
+
         let meta = MatchCreated {
             region,
             game: new_match.game,
@@ -90,8 +152,8 @@ impl WriteMatchManager {
                 .into_iter()
                 .zip(write.into_iter())
                 .collect(),
-            ai_players: new_match.ai_players,
-            read: read.to_string(),
+            ai_players,
+            read: match_id.clone(),
             url_pub: public_url,
             url_priv: private_url,
         };
@@ -100,35 +162,47 @@ impl WriteMatchManager {
             instance: instance.clone(),
             meta,
             logger,
-            match_id: read.to_string(),
+            match_id: match_id.clone(),
             write_connected: RwLock::new(HashMap::new()),
             exited: AtomicI8::new(0),
             started: AtomicBool::new(false),
             awaiting_reconnection: std::sync::Mutex::new(HashMap::new()),
             on_exit_callbacks: std::sync::Mutex::new(Vec::new()),
             min_players,
-            bummerl: new_match.mode == "bummerl",
+            bummerl: mode.length == Length::Bummerl,
+            next_round_delay: mode.next_round_delay,
+            performances: std::sync::Mutex::new(Performances::new(&mode.ranking)),
             round_begin_timestamp: AtomicU64::new(chrono::Utc::now().timestamp_micros() as u64),
+            io: io.clone(),
+            bot_takeover: mode.bot_takeover,
+            takeovers: std::sync::Mutex::new(HashSet::new()),
+            max_auto_moves: mode.auto_moves,
+            auto_moves: std::sync::Mutex::new(HashMap::new()),
+            turns: std::sync::Mutex::new(HashMap::new()),
+            clock,
+            deadlines: mode.timeouts.clone(),
+            naming: mode.naming,
+            decisions: std::sync::Mutex::new(HashMap::new()),
+            tokens,
+            token_generations: std::sync::Mutex::new(HashMap::new()),
         });
 
         {
             let new = new.clone();
 
             let public_room_setup = Arc::new(AtomicBool::new(false));
+            let naming = mode.naming;
 
-            io.ns(format!("/{read}"), {
+            io.ns(format!("/{match_id}"), {
                 move |socket: SocketRef| {
                     if !public_room_setup.load(std::sync::atomic::Ordering::SeqCst) {
                         let socket = socket.clone();
                         instance.lock().unwrap().on_pub_event(move |event| {
-                            emitter::to_public_event_emitter(
-                                &event.clone().into() as &TimedEvent<PublicEvent>
-                            )(socket.to(PUBLIC_EVENT_ROOM))
-                            .unwrap();
-                            emitter::to_private_event_emitter(
-                                &event.into() as &TimedEvent<PublicEvent>
-                            )(socket.clone())
-                            .unwrap();
+                            let event: TimedEvent<PublicEvent> = event.into();
+                            let event = Named::new(&event, naming);
+                            emitter::to_public_event_emitter(&event)(socket.to(PUBLIC_EVENT_ROOM))
+                                .unwrap();
+                            emitter::to_private_event_emitter(&event)(socket.clone()).unwrap();
                         });
                         public_room_setup.store(true, std::sync::atomic::Ordering::SeqCst);
                     }
@@ -137,14 +211,25 @@ impl WriteMatchManager {
             });
         }
 
+        for player_id in new.meta.player_write.keys() {
+            if new.meta.ai_players.contains(player_id) {
+                new.clone().setup_ai_player(player_id.clone());
+            } else {
+                new.clone().setup_wait_for_move(player_id.clone());
+            }
+        }
+
+        new.clone().setup_performances();
         new.clone().setup_match_result_handler();
+        if new.clock.is_some() {
+            new.clone().setup_clock();
+        }
         if new.bummerl {
             new.clone().setup_match_final_result_handler();
         }
 
         new.clone().await_initial_connection();
 
-        // TODO: The created match should be added to some active-state
         new
     }
 
@@ -169,6 +254,71 @@ impl WriteMatchManager {
             .collect()
     }
 
+    pub fn phase(&self) -> Phase {
+        if self.exited.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+            Phase::Finished
+        } else if !self.started.load(std::sync::atomic::Ordering::SeqCst) {
+            Phase::Waiting
+        } else if self.instance.lock().unwrap().round_outcome().is_some() {
+            Phase::BetweenRounds
+        } else {
+            Phase::Playing
+        }
+    }
+
+    pub fn summary(&self) -> MatchSummary {
+        let phase = self.phase();
+        let points: HashMap<_, _> = {
+            let instance = self.instance.lock().unwrap();
+            self.meta
+                .player_write
+                .keys()
+                .filter_map(|player_id| {
+                    let player = instance.get_player(player_id)?;
+                    let player = player.read().unwrap();
+                    Some((player_id.clone(), (player.points, player.trick_points())))
+                })
+                .collect()
+        };
+
+        let write_connected = self.write_connected.read().unwrap();
+        let takeovers = self.takeovers.lock().unwrap();
+        let mut players: Vec<_> = self
+            .meta
+            .player_write
+            .keys()
+            .map(|player_id| {
+                let (game_points, trick_points) =
+                    points.get(player_id).copied().unwrap_or_default();
+                PlayerSummary {
+                    id: player_id.clone(),
+                    ai: self.meta.ai_players.contains(player_id),
+                    taken_over: takeovers.contains(player_id),
+                    connected_sockets: write_connected.get(player_id).map_or(0, Vec::len),
+                    game_points,
+                    trick_points,
+                }
+            })
+            .collect();
+        players.sort_by(|a, b| a.id.cmp(&b.id));
+
+        MatchSummary {
+            match_id: self.match_id.clone(),
+            mode: self.meta.mode.clone(),
+            phase,
+            players,
+        }
+    }
+
+    /// Ends the match without a result, and tells the players why.
+    pub fn abort(self: Arc<Self>, reason: String) {
+        debug!("Aborting match {:?}: {}", self.match_id, reason);
+        self.emit_server_event(ServerEvent::Aborted {
+            reason: reason.clone(),
+        });
+        self.exit(Err(MatchError::Aborted(reason)));
+    }
+
     pub fn on_exit<F>(self: Arc<Self>, callback: F)
     where
         F: FnOnce(Result<MatchResult, MatchAbruptClose>) + Send + Sync + 'static,
@@ -194,6 +344,29 @@ impl WriteMatchManager {
         for callback in self.on_exit_callbacks.lock().unwrap().drain(..) {
             callback(reason.clone());
         }
+
+        async_std::task::spawn(self.teardown());
+    }
+
+    /// Lets go of everything the finished match holds on to after the grace period: the sockets,
+    /// the namespace and the listeners on the engine, most of which point back to the match. The
+    /// engine is dropped along with the match.
+    async fn teardown(self: Arc<Self>) {
+        async_std::task::sleep(Duration::from_secs(TEARDOWN_GRACE)).await;
+        debug!("Tearing down match {:?}", self.match_id);
+
+        let namespace = format!("/{}", self.match_id);
+        if let Some(operators) = self.io.of(namespace.as_str()) {
+            if let Err(err) = operators.disconnect() {
+                error!("Error disconnecting from {:?}: {:?}", self.match_id, err);
+            }
+        }
+        self.io.delete_ns(namespace.as_str());
+
+        self.write_connected.write().unwrap().clear();
+        self.awaiting_reconnection.lock().unwrap().clear();
+        self.turns.lock().unwrap().clear();
+        self.instance.lock().unwrap().clear_callbacks();
     }
 
     #[inline]
@@ -208,12 +381,163 @@ impl WriteMatchManager {
 
     fn await_initial_connection(self: Arc<Self>) {
         for player in self.meta.player_write.keys() {
+            if self.meta.ai_players.contains(player) {
+                continue;
+            }
+
             let (tx, rx) = watch::channel(false);
             self.awaiting_reconnection
                 .lock()
                 .unwrap()
                 .insert(player.clone(), tx);
-            tokio::spawn(self.clone().await_timeout(rx, player.clone()));
+            tokio::spawn(self.clone().await_timeout(
+                rx,
+                player.clone(),
+                Duration::from_secs(CONNECT_TIMEOUT),
+            ));
+        }
+    }
+
+    /// Changes with every event `player_id` sees and once the match is over.
+    fn watch_turns(self: &Arc<Self>, player_id: &str) -> Receiver<()> {
+        let mut turns = self.turns.lock().unwrap();
+        if let Some(tx) = turns.get(player_id) {
+            return tx.subscribe();
+        }
+
+        let (tx, rx) = watch::channel(());
+        let tx = Arc::new(tx);
+        {
+            let mut instance_lock = self.instance.lock().unwrap();
+            let player = instance_lock.get_player(player_id).unwrap();
+
+            let on_priv_event = tx.clone();
+            instance_lock.on_priv_event(player, move |_| {
+                on_priv_event.send_replace(());
+            });
+            let on_pub_event = tx.clone();
+            instance_lock.on_pub_event(move |_| {
+                on_pub_event.send_replace(());
+            });
+        }
+        {
+            let tx = tx.clone();
+            self.clone().on_exit(move |_| {
+                tx.send_replace(());
+            });
+        }
+
+        turns.insert(player_id.to_string(), tx);
+        rx
+    }
+
+    /// Lets an agent play the seat of `player_id` on the engine. The seat counts as connected, but
+    /// has no sockets.
+    fn setup_ai_player(self: Arc<Self>, player_id: String) {
+        let Some(ai_player) = ai_player::AiPlayer::seat(&self, &player_id) else {
+            error!("AI player not found: {:?}", player_id);
+            return;
+        };
+
+        self.write_connected
+            .write()
+            .unwrap()
+            .insert(player_id.clone(), Vec::new());
+
+        let turns = self.watch_turns(&player_id);
+        tokio::spawn(ai_player.play(self, turns));
+    }
+
+    /// Lets a bot play for the disconnected player until they reconnect.
+    fn takeover(self: Arc<Self>, player_id: String) {
+        let Some(stand_in) = ai_player::AiPlayer::stand_in(&self, &player_id) else {
+            error!("Player not found: {:?}", player_id);
+            return;
+        };
+        if !self.takeovers.lock().unwrap().insert(player_id.clone()) {
+            return;
+        }
+
+        debug!("Taking over for player: {:?}", player_id);
+        self.emit_server_event(ServerEvent::Takeover {
+            user_id: player_id.clone(),
+        });
+
+        let turns = self.watch_turns(&player_id);
+        tokio::spawn(stand_in.play(self, turns));
+    }
+
+    fn handback(&self, player_id: &str) {
+        if !self.takeovers.lock().unwrap().remove(player_id) {
+            return;
+        }
+
+        debug!("Handing back to player: {:?}", player_id);
+        self.emit_server_event(ServerEvent::Handback {
+            user_id: player_id.to_string(),
+        });
+    }
+
+    /// Logs the event and sends it to everyone in the match.
+    fn emit_server_event(&self, event: ServerEvent) {
+        let timed_event: TimedEvent<ServerEvent> = event.clone().into();
+        self.logger.lock().unwrap().log(
+            TimedEvent {
+                event: SchnapsenDuoEventType::Server(event),
+                timestamp: timed_event.timestamp,
+            },
+            None,
+        );
+
+        let namespace = format!("/{}", self.match_id);
+        if let Some(operators) = self.io.of(namespace.as_str()) {
+            if let Err(err) =
+                emitter::to_public_event_emitter(&timed_event)(operators.to(PUBLIC_EVENT_ROOM))
+            {
+                error!("Error emitting server event: {:?}", err);
+            }
+        }
+    }
+
+    /// Runs the clock of the active player and sends the clocks to everyone whenever it changes
+    /// hands.
+    fn setup_clock(self: Arc<Self>) {
+        self.clone()
+            .instance
+            .lock()
+            .unwrap()
+            .on_pub_event(move |event| {
+                let Some(clock) = &self.clock else {
+                    return;
+                };
+                let state = {
+                    let mut clock = clock.lock().unwrap();
+                    match event {
+                        PublicEvent::Active { user_id } => clock.start(&user_id),
+                        PublicEvent::PlayCard { user_id, .. } => {
+                            clock.on_move(&user_id);
+                            return;
+                        }
+                        PublicEvent::Result { .. } if clock.control().flag == Flag::Round => {
+                            clock.refill()
+                        }
+                        PublicEvent::Result { .. } => clock.stop(),
+                        _ => return,
+                    }
+                    clock.state()
+                };
+                self.emit_server_event(state);
+            });
+    }
+
+    /// How long the player has for the decision. Moves are limited by what is left of their bank
+    /// if the mode is played with a clock.
+    fn decision_timeout(&self, player_id: &str, decision: Decision) -> Duration {
+        match &self.clock {
+            Some(clock) if decision == Decision::PlayCard => {
+                clock.lock().unwrap().remaining(player_id)
+            }
+            _ => self.deadlines.timeout(decision),
         }
     }
 
@@ -249,19 +573,23 @@ impl WriteMatchManager {
                     });
                 }
 
-                async_std::task::sleep(Duration::from_secs(option_env!("NEXT_ROUND_DELAY").unwrap().parse().unwrap())).await;
+                async_std::task::sleep(match_manager.next_round_delay).await;
 
                 self.round_begin_timestamp
                     .store(reset_time, std::sync::atomic::Ordering::SeqCst);
 
                 let mut instance_lock = match_manager.instance.lock().unwrap();
                 let player = instance_lock.get_player(&winner).unwrap();
-                instance_lock.next_round(player);
+                instance_lock.open_next_round(player);
             });
             return;
         }
 
-        let points = self.instance.lock().unwrap().calc_points().unwrap();
+        let mut points = self.instance.lock().unwrap().calc_points().unwrap();
+        // The winner of the round doesn't need to have more points, e.g. if the other conceded
+        if *points.winner.player.read().unwrap().id != winner {
+            std::mem::swap(&mut points.winner, &mut points.loser);
+        }
 
         debug!("Reporting Match Result as: {:?}", points);
 
@@ -276,14 +604,23 @@ impl WriteMatchManager {
                 Self::to_bummerl_points(points.loser.points),
             )]),
             event_log: self.get_event_log(),
-            ranking: Ranking {
-                performances: HashMap::from_iter(vec![]),
-            },
+            ranking: self.performances.lock().unwrap().ranking(),
         };
 
         self.clone().exit(Ok(result));
     }
 
+    /// Notes the performances of the players the mode ranks them by.
+    fn setup_performances(self: Arc<Self>) {
+        self.clone()
+            .instance
+            .lock()
+            .unwrap()
+            .on_pub_event(move |event| {
+                self.performances.lock().unwrap().on_event(&event);
+            });
+    }
+
     fn setup_match_result_handler(self: Arc<Self>) {
         self.clone()
             .instance
@@ -291,8 +628,10 @@ impl WriteMatchManager {
             .unwrap()
             .on_pub_event(move |event| {
                 // TODO|POTERROR: Change this to final result
-                if let PublicEvent::Result { winner, .. } = event {
-                    self.clone().on_match_result(winner);
+                if let PublicEvent::Result { winner, .. } = &event {
+                    // The round may be ranked before the listener of the performances saw it
+                    self.performances.lock().unwrap().on_event(&event);
+                    self.clone().on_match_result(winner.clone());
                 }
             });
     }
@@ -312,9 +651,7 @@ impl WriteMatchManager {
                         winners: HashMap::from_iter(vec![(winner.clone(), winner_points.clone())]),
                         losers: HashMap::from_iter(vec![(loser.clone(), loser_points.clone())]),
                         event_log: self.get_event_log(),
-                        ranking: Ranking {
-                            performances: HashMap::from_iter(vec![]),
-                        },
+                        ranking: self.performances.lock().unwrap().ranking(),
                     };
 
                     self.clone().exit(Ok(result));
@@ -322,10 +659,11 @@ impl WriteMatchManager {
             });
     }
 
-    fn threaten_timeout(&self, player_id: &str) {
+    fn threaten_timeout(&self, player_id: &str, decision: Decision, timeout: Duration) {
         let timeout: TimedEvent<TimeoutThreat> = TimedEvent {
             event: TimeoutThreat {
-                timeout: FORCE_MOVE_TIMEOUT,
+                timeout: timeout.as_secs(),
+                decision,
             },
             timestamp: chrono::Utc::now().timestamp_micros() as u64,
         };
@@ -338,6 +676,41 @@ impl WriteMatchManager {
         }
     }
 
+    /// Warns the player ever more urgently as the deadline of their decision draws closer, until
+    /// they made it.
+    async fn warn_before_timeout(
+        self: Arc<Self>,
+        mut rx: Receiver<bool>,
+        player_id: String,
+        decision: Decision,
+        timeout: Duration,
+    ) {
+        let deadline = Instant::now() + timeout;
+        for remaining in self.deadlines.warnings(timeout) {
+            let until_warning = deadline
+                .saturating_duration_since(Instant::now())
+                .saturating_sub(remaining);
+            select! {
+                _ = rx.changed() => return,
+                _ = async_std::task::sleep(until_warning) => {}
+            };
+
+            let warning = TimedEvent {
+                event: TimeoutWarning {
+                    remaining: remaining.as_secs(),
+                    decision,
+                },
+                timestamp: chrono::Utc::now().timestamp_micros() as u64,
+            };
+            for socket in self.get_sockets(&player_id) {
+                let warning = warning.clone();
+                async_std::task::spawn(async move {
+                    emitter::to_private_event_emitter(&warning)(socket.lock().await.clone())
+                });
+            }
+        }
+    }
+
     fn cancel_timeout_threat(&self, player_id: &str) {
         let threat_close = TimedEvent {
             event: TimeoutThreatClose::new(),
@@ -352,74 +725,277 @@ impl WriteMatchManager {
         }
     }
 
-    async fn play_card_or_timeout(self: Arc<Self>, event: PrivateEvent, player_id: String) {
-        if let PrivateEvent::AllowPlayCard = event {
-            let (tx, rx) = watch::channel(false);
-            let player_id_copy = player_id.clone();
-            let match_manager = self.clone();
-            let on_play_card = move |event| {
-                if let PublicEvent::PlayCard { user_id, card: _ } = event {
-                    if player_id_copy == user_id {
-                        let _ = tx.send(true);
-                        match_manager.cancel_timeout_threat(&player_id_copy);
-                    }
-                }
-            };
+    /// Gives the player until the deadline to make the decision the engine handed them.
+    async fn decide_or_timeout(self: Arc<Self>, event: PrivateEvent, player_id: String) {
+        let Some(decision) = Decision::of(&event) else {
+            return;
+        };
+        let deadline = {
+            let mut decisions = self.decisions.lock().unwrap();
+            let deadline = decisions.entry(player_id.clone()).or_insert(0);
+            *deadline += 1;
+            *deadline
+        };
 
-            self.instance
-                .lock()
-                .unwrap()
-                .on_pub_event(on_play_card.clone());
+        let (tx, rx) = watch::channel(false);
+        let player_id_copy = player_id.clone();
+        let match_manager = self.clone();
+        let on_decision = move |event| {
+            if decision.is_made_by(&event, &player_id_copy) {
+                let _ = tx.send(true);
+                match_manager.cancel_timeout_threat(&player_id_copy);
+            }
+        };
 
-            self.threaten_timeout(&player_id);
-            self.clone().await_timeout(rx, player_id).await;
-            self.instance.lock().unwrap().off_pub_event(on_play_card);
+        self.instance
+            .lock()
+            .unwrap()
+            .on_pub_event(on_decision.clone());
+
+        let timeout = self.decision_timeout(&player_id, decision);
+        self.threaten_timeout(&player_id, decision, timeout);
+        async_std::task::spawn(self.clone().warn_before_timeout(
+            rx.clone(),
+            player_id.clone(),
+            decision,
+            timeout,
+        ));
+
+        match decision {
+            Decision::PlayCard if self.clock.is_some() => {
+                self.clone().await_clock(rx, player_id).await
+            }
+            Decision::PlayCard => {
+                self.clone()
+                    .await_move(rx, player_id, deadline, timeout)
+                    .await
+            }
+            Decision::Cut | Decision::TakeCards => {
+                self.clone()
+                    .await_decision(rx, player_id, decision, deadline, timeout)
+                    .await
+            }
         }
+        self.instance.lock().unwrap().off_pub_event(on_decision);
     }
 
-    async fn await_timeout(self: Arc<Self>, mut rx: Receiver<bool>, player_id: String) {
+    async fn await_timeout(
+        self: Arc<Self>,
+        mut rx: Receiver<bool>,
+        player_id: String,
+        timeout: Duration,
+    ) {
         select! {
             _ = rx.changed() => { },
-            _ = async_std::task::sleep(Duration::from_secs(FORCE_MOVE_TIMEOUT)) => {
-                let mut losers = HashMap::new();
-                losers.insert(player_id.clone(), 0 as u8);
+            _ = async_std::task::sleep(timeout) => {
+                self.forfeit(player_id);
+            }
+        };
+    }
 
-                let winners: HashMap<_, _> = self
-                    .write_connected
-                    .read()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|(other_id, _)| {
-                        if player_id != **other_id {
-                            Some((other_id.clone(), 0 as u8))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-
-                let result = MatchResult {
-                    match_id: self.match_id.clone(),
-                    winners,
-                    losers,
-                    ranking: Ranking {
-                        performances: HashMap::new(),
-                    },
-                    event_log: self.get_event_log(),
-                };
+    /// Waits for the player to decide, they forfeit once the time is up.
+    async fn await_decision(
+        self: Arc<Self>,
+        mut rx: Receiver<bool>,
+        player_id: String,
+        decision: Decision,
+        deadline: u64,
+        timeout: Duration,
+    ) {
+        select! {
+            _ = rx.changed() => { },
+            _ = async_std::task::sleep(timeout) => {
+                // The decision may have been made before the deadline listened for it
+                if self.is_current_deadline(&player_id, deadline)
+                    && self.is_pending(&player_id, decision)
+                {
+                    self.forfeit(player_id);
+                }
+            }
+        };
+    }
 
-                self.clone().timeout_player(player_id.clone());
+    /// Whether no decision was handed to the player since the deadline was armed.
+    fn is_current_deadline(&self, player_id: &str, deadline: u64) -> bool {
+        self.decisions.lock().unwrap().get(player_id) == Some(&deadline)
+    }
 
-                if self.write_connected.read().unwrap().iter().any(|(_, v)| v.len() < self.min_players) {
-                    self.clone().exit(Ok(result));
-                    return;
+    /// Whether the engine still waits for the player to make the decision.
+    fn is_pending(&self, player_id: &str, decision: Decision) -> bool {
+        let instance = self.instance.lock().unwrap();
+        let on_turn = instance
+            .get_active_player()
+            .is_some_and(|active| active.read().unwrap().id == player_id);
+        let opening = match decision {
+            Decision::PlayCard => None,
+            Decision::Cut => Some(Opening::Cut),
+            Decision::TakeCards => Some(Opening::Draw),
+        };
+        on_turn && instance.opening() == opening
+    }
+
+    /// Waits for the player to move. Once the time is up, the server plays a card for them,
+    /// unless it already did so `max_auto_moves` times in a row.
+    async fn await_move(
+        self: Arc<Self>,
+        mut rx: Receiver<bool>,
+        player_id: String,
+        deadline: u64,
+        timeout: Duration,
+    ) {
+        select! {
+            _ = rx.changed() => {
+                self.auto_moves.lock().unwrap().remove(&player_id);
+            },
+            _ = async_std::task::sleep(timeout) => {
+                if !self.auto_move(&player_id, deadline) {
+                    self.forfeit(player_id);
                 }
+            }
+        };
+    }
+
+    /// Waits for the player to move before their bank runs out.
+    async fn await_clock(self: Arc<Self>, mut rx: Receiver<bool>, player_id: String) {
+        loop {
+            // Their clock may only have started after the move was asked for
+            let remaining = self.decision_timeout(&player_id, Decision::PlayCard);
+            if remaining.is_zero() {
+                self.flag(player_id);
+                return;
+            }
+
+            select! {
+                _ = rx.changed() => return,
+                _ = async_std::task::sleep(remaining) => {}
+            };
+        }
+    }
 
-                if !self.started.load(std::sync::atomic::Ordering::SeqCst) {
-                    self.exit(Ok(result));
+    /// The player ran out of time, and loses what the time control says.
+    fn flag(self: Arc<Self>, player_id: String) {
+        let Some(control) = self
+            .clock
+            .as_ref()
+            .map(|clock| clock.lock().unwrap().control())
+        else {
+            return;
+        };
+
+        debug!("Player flagged: {:?}", player_id);
+        self.emit_server_event(ServerEvent::Flagged {
+            user_id: player_id.clone(),
+            lost: control.flag,
+        });
+
+        match control.flag {
+            Flag::Round => {
+                let mut instance = self.instance.lock().unwrap();
+                let Some(player) = instance.get_player(&player_id) else {
+                    return;
+                };
+                if let Err(err) = instance.concede_round(player) {
+                    error!("Could not concede round of {:?}: {:?}", player_id, err);
                 }
             }
+            Flag::Bummerl => {
+                let result = self.forfeit_result(&player_id);
+                self.exit(Ok(result));
+            }
+        }
+    }
+
+    /// Plays one of the playable cards for the player, the way a greedy bot would. Whether the
+    /// player gets away with missing the deadline: if it was superseded by a later decision or it
+    /// isn't their turn anymore, there is nothing to make up for.
+    fn auto_move(&self, player_id: &str, deadline: u64) -> bool {
+        if !self.is_current_deadline(player_id, deadline) {
+            debug!("Deadline of player {:?} was superseded", player_id);
+            return true;
+        }
+
+        let Some(player) = self.instance.lock().unwrap().get_player(player_id) else {
+            return false;
+        };
+        if !self.is_pending(player_id, Decision::PlayCard) {
+            debug!("Player {:?} is no longer on turn", player_id);
+            return true;
+        }
+
+        {
+            let mut auto_moves = self.auto_moves.lock().unwrap();
+            let count = auto_moves.entry(player_id.to_string()).or_insert(0);
+            if *count >= self.max_auto_moves {
+                return false;
+            }
+            *count += 1;
+        }
+
+        let client = SchnapsenDuoClient::new(player, self.instance.clone());
+        let cards: Vec<_> = client
+            .legal_actions()
+            .into_iter()
+            .filter(|action| matches!(action, Action::PlayCard(_)))
+            .collect();
+        let Some(observation) = client.observe().filter(|_| !cards.is_empty()) else {
+            return false;
         };
+
+        let action = GreedyAgent::new().act(&observation, &cards);
+        if let Err(err) = client.perform(action) {
+            error!("Could not move for {:?}: {:?}", player_id, err);
+            return false;
+        }
+
+        debug!("Moved for player: {:?}", player_id);
+        self.emit_server_event(ServerEvent::AutomatedMove {
+            user_id: player_id.to_string(),
+            action,
+        });
+        true
+    }
+
+    /// A result in which the player lost against everyone else.
+    fn forfeit_result(&self, player_id: &str) -> MatchResult {
+        let mut losers = HashMap::new();
+        losers.insert(player_id.to_string(), 0 as u8);
+
+        let winners: HashMap<_, _> = self
+            .write_connected
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|(other_id, _)| {
+                if *other_id != player_id {
+                    Some((other_id.clone(), 0 as u8))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        MatchResult {
+            match_id: self.match_id.clone(),
+            winners,
+            losers,
+            ranking: self.performances.lock().unwrap().ranking(),
+            event_log: self.get_event_log(),
+        }
+    }
+
+    fn forfeit(self: Arc<Self>, player_id: String) {
+        let result = self.forfeit_result(&player_id);
+
+        self.clone().timeout_player(player_id.clone());
+
+        if self.write_connected.read().unwrap().iter().any(|(_, v)| v.len() < self.min_players) {
+            self.clone().exit(Ok(result));
+            return;
+        }
+
+        if !self.started.load(std::sync::atomic::Ordering::SeqCst) {
+            self.exit(Ok(result));
+        }
     }
 
     fn setup_wait_for_move(self: Arc<Self>, player_id: String) {
@@ -435,7 +1011,7 @@ impl WriteMatchManager {
             .lock()
             .unwrap()
             .on_priv_event(player, move |event| {
-                async_std::task::spawn(self.clone().play_card_or_timeout(event, player_id.clone()));
+                async_std::task::spawn(self.clone().decide_or_timeout(event, player_id.clone()));
             });
     }
 
@@ -492,23 +1068,100 @@ impl WriteMatchManager {
         logger
     }
 
+    /// The player the write token was issued for, if it is for this match and was neither revoked
+    /// nor has expired.
+    pub fn player_of(&self, write: &str) -> Option<String> {
+        let claims = match self.tokens.verify(write) {
+            Ok(claims) => claims,
+            Err(err) => {
+                debug!("Refusing token at Game: {:?}: {}", self.match_id, err);
+                return None;
+            }
+        };
+        if claims.match_id != self.match_id {
+            debug!(
+                "Refusing token for Game: {:?} at Game: {:?}",
+                claims.match_id, self.match_id
+            );
+            return None;
+        }
+        if !self.meta.player_write.contains_key(&claims.player_id) {
+            return None;
+        }
+
+        let generation = self.token_generation(&claims.player_id);
+        if claims.generation != generation {
+            debug!("Refusing revoked token of player: {:?}", claims.player_id);
+            return None;
+        }
+        Some(claims.player_id)
+    }
+
+    fn token_generation(&self, player_id: &str) -> u32 {
+        self.token_generations
+            .lock()
+            .unwrap()
+            .get(player_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// A new write token for the player, the ones issued earlier stay valid until revoked.
+    pub fn issue_token(&self, player_id: &str) -> Option<String> {
+        if !self.meta.player_write.contains_key(player_id) {
+            return None;
+        }
+        let generation = self.token_generation(player_id);
+        Some(self.tokens.issue(&self.match_id, player_id, generation))
+    }
+
+    /// Removes the player from the match: their tokens are refused from now on and their sockets
+    /// are disconnected, as if they left. Only tokens issued afterwards let them back in.
+    pub fn revoke(&self, player_id: &str) -> bool {
+        if !self.meta.player_write.contains_key(player_id) {
+            return false;
+        }
+        debug!("Revoking tokens of player: {:?}", player_id);
+        *self
+            .token_generations
+            .lock()
+            .unwrap()
+            .entry(player_id.to_string())
+            .or_insert(0) += 1;
+
+        let sockets = self
+            .write_connected
+            .read()
+            .unwrap()
+            .get(player_id)
+            .cloned()
+            .unwrap_or_default();
+        for socket in sockets {
+            async_std::task::spawn(async move {
+                if let Err(err) = socket.lock().await.clone().disconnect() {
+                    error!("Error disconnecting revoked socket: {:?}", err);
+                }
+            });
+        }
+        true
+    }
+
     async fn setup_private_access(
         self: Arc<Self>,
         write: &str,
         socket: Arc<tokio::sync::Mutex<SocketRef>>,
     ) {
-        let player_id = self
-            .meta
-            .player_write
-            .iter()
-            .find_map(|(k, v)| if v == write { Some(k) } else { None })
-            .cloned();
+        let player_id = self.player_of(write);
 
         if player_id.is_none() {
             return;
         }
 
         let player_id = player_id.as_ref().unwrap();
+        if self.meta.ai_players.contains(player_id) {
+            debug!("Refusing access to the AI seat of {:?}", player_id);
+            return;
+        }
 
         self.write_connected
             .write()
@@ -520,6 +1173,16 @@ impl WriteMatchManager {
         if let Some(rx) = self.awaiting_reconnection.lock().unwrap().remove(player_id) {
             let _ = rx.send(true);
         }
+        self.handback(player_id);
+
+        // Before the replay, so the client knows which of the players it is
+        let seat: TimedEvent<Seat> = Seat {
+            user_id: player_id.clone(),
+        }
+        .into();
+        if let Err(err) = emitter::to_private_event_emitter(&seat)(socket.lock().await.clone()) {
+            error!("Error emitting seat: {:?}", err);
+        }
 
         if self.started.load(std::sync::atomic::Ordering::SeqCst) {
             tokio::spawn(
@@ -544,6 +1207,7 @@ impl WriteMatchManager {
         debug!("Got player: {:?}", player.read().unwrap().id);
 
         let socket_clone = socket.clone();
+        let naming = self.naming;
         self.instance
             .lock()
             .unwrap()
@@ -551,17 +1215,16 @@ impl WriteMatchManager {
                 debug!("Got private event: {:?}", event);
                 let socket_clone = socket_clone.clone();
                 async_std::task::spawn(async move {
-                    if let Err(err) = emitter::to_private_event_emitter(
-                        &event.into() as &TimedEvent<PrivateEvent>
-                    )(socket_clone.lock().await.clone())
+                    let event: TimedEvent<PrivateEvent> = event.into();
+                    let socket = socket_clone.lock().await.clone();
+                    if let Err(err) =
+                        emitter::to_private_event_emitter(&Named::new(&event, naming))(socket)
                     {
                         error!("Error emitting private event: {:?}", err);
-                    }
+                    };
                 });
             });
 
-        self.clone().setup_wait_for_move(player_id.to_string());
-
         let player_id_clone = player_id.to_string();
         let instance = self.instance.clone();
 
@@ -606,6 +1269,10 @@ impl WriteMatchManager {
         socket.lock().await.on_disconnect(
             move |disconnected: SocketRef, reason: DisconnectReason| {
                 debug!("Player: {:?} disconnected", player_id);
+                // The match is over, no one has to reconnect
+                if self.exited.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+                    return;
+                }
 
                 let should_exit = 'exit: {
                     let mut lock = self.write_connected.write().unwrap();
@@ -640,6 +1307,11 @@ impl WriteMatchManager {
     }
 
     async fn reconnect_or_timeout(self: Arc<Self>, player_id: String) {
+        if self.bot_takeover && self.started.load(std::sync::atomic::Ordering::SeqCst) {
+            self.takeover(player_id);
+            return;
+        }
+
         let (tx, mut rx) = watch::channel(false);
 
         self.awaiting_reconnection
@@ -647,7 +1319,8 @@ impl WriteMatchManager {
             .unwrap()
             .insert(player_id.clone(), tx);
 
-        self.await_timeout(rx, player_id).await;
+        self.await_timeout(rx, player_id, Duration::from_secs(CONNECT_TIMEOUT))
+            .await;
     }
 
     async fn handle_auth(
@@ -655,17 +1328,21 @@ impl WriteMatchManager {
         data: String,
         socket: Arc<tokio::sync::Mutex<SocketRef>>,
     ) {
-        debug!("Authenticating: {:?} at Game: {:?}", data, self.match_id);
+        let Some(player_id) = self.player_of(&data) else {
+            debug!("Unknown write token at Game: {:?}", self.match_id);
+            return;
+        };
+        debug!("Authenticating: {:?} at Game: {:?}", player_id, self.match_id);
 
         self.clone()
             .setup_private_access(&data.clone(), socket.clone())
             .await;
-        debug!("Authenticated: {:?} at Game: {:?}", data, self.match_id);
+        debug!("Authenticated: {:?} at Game: {:?}", player_id, self.match_id);
 
         if self.write_connected.read().unwrap().len() == self.meta.player_write.len()
             && !self.started.swap(true, std::sync::atomic::Ordering::SeqCst)
         {
-            self.start_match(data);
+            self.start_match(player_id);
         };
     }
 
@@ -685,6 +1362,7 @@ impl WriteMatchManager {
             .collect();
 
         for timed_event in events {
+            let timed_event = Named::new(&timed_event, self.naming);
             emitter::to_private_event_emitter(&timed_event)(socket.lock().await.clone()).unwrap();
         }
     }
@@ -741,7 +1419,7 @@ impl WriteMatchManager {
         debug!("Starting game: {:?}", self.match_id);
         let active_player = lock.get_player(&begin_player_id);
         lock.set_active_player(active_player.unwrap()).unwrap();
-        lock.distribute_cards().unwrap();
+        lock.open_round().unwrap();
     }
 
     async fn setup_read_ns(self: Arc<Self>, socket: SocketRef) {
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use gn_communicator::models::RankingConf;
+use schnapsen_rs::{models::AnnounceType, PublicEvent};
+
+use crate::models::Ranking;
+
+/// The performances the server can tell from a match, by the names modes rank them with.
+pub const KNOWN: [&str; 5] = ["schneider", "schwarz", "forty", "twenty", "close_talon"];
+
+/// The performance the event shows, and who achieved it.
+fn achieved(event: &PublicEvent) -> Option<(&'static str, &str)> {
+    match event {
+        // A round won for two game points left the loser in Schneider, for three without a trick
+        PublicEvent::Result { winner, points, .. } => match points {
+            2 => Some(("schneider", winner)),
+            3 => Some(("schwarz", winner)),
+            _ => None,
+        },
+        PublicEvent::Announce {
+            user_id,
+            announcement,
+        } => match announcement.announce_type {
+            AnnounceType::Forty => Some(("forty", user_id)),
+            AnnounceType::Twenty => Some(("twenty", user_id)),
+        },
+        PublicEvent::CloseTalon { user_id } => Some(("close_talon", user_id)),
+        _ => None,
+    }
+}
+
+/// Who achieved the performances the mode ranks by, over all rounds of a match.
+pub struct Performances {
+    achieved: HashMap<String, Vec<String>>,
+}
+
+impl Performances {
+    pub fn new(conf: &RankingConf) -> Self {
+        Self {
+            achieved: conf
+                .performances
+                .iter()
+                .map(|performance| (performance.name.clone(), Vec::new()))
+                .collect(),
+        }
+    }
+
+    pub fn on_event(&mut self, event: &PublicEvent) {
+        let Some((performance, player_id)) = achieved(event) else {
+            return;
+        };
+        // Performances the mode doesn't rank by aren't tracked
+        if let Some(players) = self.achieved.get_mut(performance) {
+            if !players.iter().any(|other| other == player_id) {
+                players.push(player_id.to_string());
+            }
+        }
+    }
+
+    /// Every performance of the mode, with the players who achieved it so far.
+    pub fn ranking(&self) -> Ranking {
+        Ranking {
+            performances: self.achieved.clone(),
+        }
+    }
+}
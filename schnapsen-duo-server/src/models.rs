@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     hash::{Hash, Hasher},
 };
 
@@ -62,6 +62,27 @@ pub struct Timeout {
     pub reason: String,
 }
 
+/// A decision the engine hands to a player, which they have to make before a deadline.
+#[derive(Serialize, Hash, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    PlayCard,
+    /// Cutting the deck before the deal.
+    Cut,
+    /// Taking up the first packet of the deal.
+    TakeCards,
+}
+
+/// What a player loses once their time bank runs out.
+#[derive(Serialize, Deserialize, Hash, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Flag {
+    /// The bank lasts one round, which the opponent wins as if it had been conceded.
+    Round,
+    /// The bank lasts the whole match, which the player loses.
+    Bummerl,
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct Ranking {
     pub performances: HashMap<String, Vec<String>>,
@@ -80,6 +101,51 @@ pub struct MatchResult {
 pub enum MatchError {
     AllPlayersDisconnected,
     PlayerDidNotJoin(String),
+    /// Ended by an admin, for the given reason.
+    Aborted(String),
+}
+
+/// How far a match has come.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Not everyone has joined yet.
+    Waiting,
+    Playing,
+    /// The next round of the bummerl is about to be dealt.
+    BetweenRounds,
+    /// Over, but not yet torn down.
+    Finished,
+}
+
+/// A player of a running match, as the admin API shows them.
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerSummary {
+    pub id: String,
+    pub ai: bool,
+    /// Whether a bot currently plays for them.
+    pub taken_over: bool,
+    pub connected_sockets: usize,
+    pub game_points: u8,
+    pub trick_points: u8,
+}
+
+/// A running match, as the admin API shows it.
+#[derive(Serialize, Debug, Clone)]
+pub struct MatchSummary {
+    pub match_id: String,
+    pub mode: String,
+    pub phase: Phase,
+    pub players: Vec<PlayerSummary>,
+}
+
+/// How many matches are running, and how many players are connected to them.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct MatchCounts {
+    pub matches: usize,
+    pub by_mode: BTreeMap<String, usize>,
+    pub by_phase: BTreeMap<Phase, usize>,
+    pub connected_sockets: usize,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -100,6 +166,10 @@ impl Into<gn_communicator::models::MatchAbrubtClose> for MatchAbruptClose {
                 MatchError::PlayerDidNotJoin(player_id) => {
                     gn_communicator::models::MatchError::PlayerDidNotJoin(player_id)
                 }
+                // The matchmaker knows no other way for a match to end without a result
+                MatchError::Aborted(_) => {
+                    gn_communicator::models::MatchError::AllPlayersDisconnected
+                }
             },
         }
     }
@@ -0,0 +1,122 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use gn_communicator::models::RankingConf;
+use schnapsen_rs::models::Naming;
+use serde::{Deserialize, Deserializer};
+use tracing::{info, warn};
+
+use crate::match_manager::{Deadlines, TimeControl, PERFORMANCES};
+
+/// The modes the server offers if no other config is given.
+const DEFAULT_MODES: &str = include_str!("../modes.yml");
+
+/// The rules of the game a mode is played with.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    /// Two players with the 20-card deck.
+    Duo,
+}
+
+/// How long a match of the mode lasts.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Length {
+    /// The match ends with the first round.
+    Round,
+    /// Rounds are played until someone reaches 7 game points.
+    Bummerl,
+}
+
+/// Everything a match of a mode is played by.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModeConfig {
+    pub variant: Variant,
+    pub length: Length,
+    /// Whether a bot plays for disconnected players, instead of them timing out.
+    #[serde(default)]
+    pub bot_takeover: bool,
+    /// How often in a row the server may move for a player who ran out of time, before they
+    /// forfeit.
+    #[serde(default)]
+    pub auto_moves: u32,
+    /// Replaces the deadline per move with a time bank.
+    #[serde(default)]
+    pub clock: Option<TimeControl>,
+    #[serde(default)]
+    pub timeouts: Deadlines,
+    /// How long the result of a round of a bummerl is shown before the next round is dealt.
+    #[serde(default = "next_round_delay", deserialize_with = "secs")]
+    pub next_round_delay: Duration,
+    /// The names the cards are sent to the players with.
+    #[serde(default)]
+    pub naming: Naming,
+    /// The stars and performances the matchmaker ranks the players by, the performances have to
+    /// be ones the server can tell.
+    pub ranking: RankingConf,
+}
+
+fn next_round_delay() -> Duration {
+    Duration::from_secs(5)
+}
+
+/// The modes the server registers with the matchmaker and creates matches for.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Modes(BTreeMap<String, ModeConfig>);
+
+impl Modes {
+    /// Reads the file at `MODES_CONFIG`, or the modes the server ships with.
+    pub fn load() -> Self {
+        let modes = match std::env::var("MODES_CONFIG") {
+            Ok(path) if !path.is_empty() => {
+                let config = std::fs::read_to_string(&path)
+                    .unwrap_or_else(|err| panic!("Can't read modes from {path}: {err}"));
+                Self::parse(&config).unwrap_or_else(|err| panic!("Invalid modes in {path}: {err}"))
+            }
+            _ => Self::parse(DEFAULT_MODES).expect("Invalid default modes"),
+        };
+        info!("Offering modes: {:?}", modes.names().collect::<Vec<_>>());
+        for (mode, config) in modes.iter() {
+            for performance in &config.ranking.performances {
+                if !PERFORMANCES.contains(&performance.name.as_str()) {
+                    warn!("No one can achieve {:?} in {mode}", performance.name);
+                }
+            }
+        }
+        modes
+    }
+
+    pub fn parse(config: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(config)
+    }
+
+    #[inline]
+    pub fn get(&self, mode: &str) -> Option<&ModeConfig> {
+        self.0.get(mode)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ModeConfig)> {
+        self.0.iter()
+    }
+}
+
+/// Reads a duration given in seconds.
+pub fn secs<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
+
+/// Reads a list of durations given in seconds.
+pub fn secs_list<'de, D>(deserializer: D) -> Result<Vec<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<u64>::deserialize(deserializer)
+        .map(|secs| secs.into_iter().map(Duration::from_secs).collect())
+}
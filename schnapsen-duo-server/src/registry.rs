@@ -0,0 +1,75 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{match_manager::WriteMatchManager, models::MatchCounts};
+
+/// The matches the server is hosting, until they are over.
+#[derive(Default)]
+pub struct MatchRegistry {
+    matches: RwLock<HashMap<String, Arc<WriteMatchManager>>>,
+}
+
+impl MatchRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a match under an ID no other match has, and keeps track of it until it exits. As
+    /// the ID is also the token to watch the match, it's random rather than derived from the match.
+    pub fn create(
+        self: &Arc<Self>,
+        create: impl FnOnce(String) -> Arc<WriteMatchManager>,
+    ) -> Arc<WriteMatchManager> {
+        // Held while the match is created, so no other match can take the ID in the meantime
+        let mut matches = self.matches.write().unwrap();
+        let match_id = loop {
+            let match_id = Uuid::new_v4().simple().to_string();
+            if !matches.contains_key(&match_id) {
+                break match_id;
+            }
+            error!("Match ID {:?} is already taken, drawing another", match_id);
+        };
+
+        let match_manager = create(match_id.clone());
+        matches.insert(match_id.clone(), match_manager.clone());
+        drop(matches);
+
+        let registry = Arc::downgrade(self);
+        match_manager.clone().on_exit(move |_| {
+            if let Some(registry) = registry.upgrade() {
+                registry.matches.write().unwrap().remove(&match_id);
+            }
+        });
+        match_manager
+    }
+
+    pub fn get(&self, match_id: &str) -> Option<Arc<WriteMatchManager>> {
+        self.matches.read().unwrap().get(match_id).cloned()
+    }
+
+    pub fn all(&self) -> Vec<Arc<WriteMatchManager>> {
+        self.matches.read().unwrap().values().cloned().collect()
+    }
+
+    pub fn counts(&self) -> MatchCounts {
+        self.all()
+            .into_iter()
+            .map(|match_manager| match_manager.summary())
+            .fold(MatchCounts::default(), |mut counts, summary| {
+                counts.matches += 1;
+                *counts.by_mode.entry(summary.mode).or_default() += 1;
+                *counts.by_phase.entry(summary.phase).or_default() += 1;
+                counts.connected_sockets += summary
+                    .players
+                    .iter()
+                    .map(|player| player.connected_sockets)
+                    .sum::<usize>();
+                counts
+            })
+    }
+}
@@ -0,0 +1,599 @@
+pub mod helpers {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use rust_socketio::{client::Client, ClientBuilder, Payload};
+    use schnapsen_rs::{
+        agent::{Agent, GreedyAgent},
+        client::SchnapsenDuoClient,
+        SchnapsenDuo,
+    };
+    use serde_json::Value;
+    use socketioxide::SocketIo;
+
+    use crate::{
+        match_manager::WriteMatchManager, modes::ModeConfig, registry::MatchRegistry,
+        tokens::Tokens,
+    };
+
+    /// A match of alice and bob in the mode.
+    pub fn create_match(
+        io: SocketIo,
+        tokens: &Arc<Tokens>,
+        mode: &ModeConfig,
+    ) -> Arc<WriteMatchManager> {
+        std::env::set_var("PUBLIC_ADDR", "localhost");
+        std::env::set_var("PRIVATE_ADDR", "localhost");
+        std::env::set_var("REGION", "local");
+
+        let new_match = gn_communicator::models::CreateMatch {
+            game: "Schnapsen".to_string(),
+            mode: "test".to_string(),
+            players: vec!["alice".to_string(), "bob".to_string()],
+            ai_players: vec![],
+        };
+        Arc::new(MatchRegistry::new()).create(|match_id| {
+            WriteMatchManager::create(Arc::new(io), match_id, new_match, mode, 2, tokens.clone())
+        })
+    }
+
+    /// Serves socket.io on a free port, returns its URL.
+    pub async fn serve() -> (SocketIo, String) {
+        let (layer, io) = SocketIo::new_layer();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let router = axum::Router::new().layer(layer);
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (io, url)
+    }
+
+    /// Connects to the match and records everything the server emits to the socket.
+    pub fn listen(url: &str, match_id: &str, payloads: &Arc<Mutex<Vec<String>>>) -> Client {
+        let payloads = payloads.clone();
+        ClientBuilder::new(url)
+            .namespace(format!("/{match_id}"))
+            .on_any(move |event, payload, _| {
+                let payload = match payload {
+                    Payload::Text(values) => Value::from(values).to_string(),
+                    other => format!("{other:?}"),
+                };
+                let event: String = event.into();
+                payloads.lock().unwrap().push(format!("{event} {payload}"));
+            })
+            .connect()
+            .unwrap()
+    }
+
+    /// Connects with the write token and waits until the server seated the socket.
+    pub fn join(
+        url: &str,
+        match_id: &str,
+        token: &str,
+        payloads: &Arc<Mutex<Vec<String>>>,
+    ) -> Client {
+        let seated = received(payloads, "seat");
+        let player = listen(url, match_id, payloads);
+        // The server only listens for `auth` once it set up the socket
+        while received(payloads, "seat") == seated {
+            let _ = player.emit("auth", Value::from(token));
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        player
+    }
+
+    /// Plays for alice and bob on the engine until `done`, greedily once the cards are dealt and
+    /// with the first legal cut and draw before. Returns whether it got there.
+    pub async fn play_until(
+        instance: &Arc<std::sync::Mutex<SchnapsenDuo>>,
+        done: impl Fn(&SchnapsenDuo) -> bool,
+    ) -> bool {
+        let players = ["alice", "bob"].map(|player_id| {
+            let player = loop {
+                if let Some(player) = instance.lock().unwrap().get_player(player_id) {
+                    break player;
+                }
+            };
+            SchnapsenDuoClient::new(player, instance.clone())
+        });
+        for _ in 0..1000 {
+            if done(&instance.lock().unwrap()) {
+                return true;
+            }
+            for player in &players {
+                let legal = player.legal_actions();
+                if legal.is_empty() {
+                    continue;
+                }
+                let action = match player.observe() {
+                    Some(observation) => GreedyAgent::new().act(&observation, &legal),
+                    None => legal[0],
+                };
+                let _ = player.perform(action);
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        false
+    }
+
+    /// How often the server emitted the event.
+    pub fn received(payloads: &Mutex<Vec<String>>, event: &str) -> usize {
+        let prefix = format!("{event} ");
+        payloads
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|payload| payload.starts_with(&prefix))
+            .count()
+    }
+
+    /// Disconnects the clients off the runtime, as they block.
+    pub async fn disconnect(clients: Vec<Client>) {
+        tokio::task::spawn_blocking(move || {
+            for client in clients {
+                let _ = client.disconnect();
+            }
+        })
+        .await
+        .unwrap();
+    }
+}
+
+mod tokens {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use socketioxide::SocketIo;
+
+    use super::helpers::{create_match, disconnect, join, listen, play_until, serve};
+    use crate::{
+        modes::Modes,
+        tokens::{TokenError, Tokens},
+    };
+
+    #[test]
+    fn tokens_are_signed_and_expire() {
+        let tokens = Tokens::new(b"secret", Duration::from_secs(60));
+        let token = tokens.issue("match", "alice", 0);
+
+        let claims = tokens.verify(&token).unwrap();
+        assert_eq!(claims.match_id, "match");
+        assert_eq!(claims.player_id, "alice");
+
+        let forged = Tokens::new(b"guess", Duration::from_secs(60)).issue("match", "alice", 0);
+        assert_eq!(tokens.verify(&forged), Err(TokenError::InvalidSignature));
+        assert_eq!(tokens.verify("alice"), Err(TokenError::Malformed));
+
+        let expired = Tokens::new(b"secret", Duration::ZERO).issue("match", "alice", 0);
+        assert_eq!(tokens.verify(&expired), Err(TokenError::Expired));
+    }
+
+    #[tokio::test]
+    async fn tokens_only_let_players_into_their_match_until_revoked() {
+        let tokens = Arc::new(Tokens::new(b"secret", Duration::from_secs(60)));
+        let modes = Modes::load();
+        let speed = modes.get("speed").unwrap();
+        let match_manager = create_match(SocketIo::new_layer().1, &tokens, speed);
+        let other_match = create_match(SocketIo::new_layer().1, &tokens, speed);
+        let alice = &match_manager.get_meta().player_write["alice"];
+
+        assert_eq!(match_manager.player_of(alice).as_deref(), Some("alice"));
+        assert_eq!(other_match.player_of(alice), None);
+
+        assert!(match_manager.revoke("alice"));
+        assert_eq!(match_manager.player_of(alice), None);
+
+        let reissued = match_manager.issue_token("alice").unwrap();
+        assert_eq!(match_manager.player_of(&reissued).as_deref(), Some("alice"));
+        assert!(!match_manager.revoke("mallory"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn write_tokens_are_never_emitted() {
+        let (io, url) = serve().await;
+        let tokens = Arc::new(Tokens::new(b"secret", Duration::from_secs(60)));
+        let match_manager = create_match(io, &tokens, Modes::load().get("speed").unwrap());
+        let meta = match_manager.get_meta();
+        let write_tokens: Vec<_> = meta.player_write.values().cloned().collect();
+
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let clients = tokio::task::spawn_blocking({
+            let payloads = payloads.clone();
+            move || {
+                let mut clients = vec![listen(&url, &meta.read, &payloads)];
+                for token in meta.player_write.values() {
+                    clients.push(join(&url, &meta.read, token, &payloads));
+                }
+                clients
+            }
+        })
+        .await
+        .unwrap();
+
+        // Both players play on the engine, their events still go out over the sockets
+        let instance = match_manager.get_match();
+        assert!(play_until(&instance, |instance| instance.round_outcome().is_some()).await);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        for payload in payloads.lock().unwrap().iter() {
+            for token in &write_tokens {
+                assert!(
+                    !payload.contains(token.as_str()),
+                    "{token} leaked: {payload}"
+                );
+            }
+        }
+
+        disconnect(clients).await;
+    }
+}
+
+mod deadlines {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use schnapsen_rs::Opening;
+
+    use super::helpers::{create_match, disconnect, join, play_until, received, serve};
+    use crate::{match_manager::WriteMatchManager, modes::Modes, tokens::Tokens};
+
+    /// `quick` gives a second for every move and moves three times for players who miss it. The
+    /// others give two seconds for every decision and warn a second before.
+    const MODES: &str = "
+        quick:
+          variant: duo
+          length: round
+          auto_moves: 3
+          timeouts:
+            play_card: 1
+            take_cards: 1
+            warnings: []
+          ranking:
+            max_stars: 50
+            description: Quick
+            performances: []
+        stall:
+          variant: duo
+          length: round
+          timeouts:
+            play_card: 2
+            cut: 2
+            take_cards: 2
+            warnings: [1]
+          ranking:
+            max_stars: 50
+            description: Stall
+            performances: []
+        stall_bummerl:
+          variant: duo
+          length: bummerl
+          next_round_delay: 1
+          timeouts:
+            play_card: 2
+            cut: 2
+            take_cards: 2
+            warnings: [1]
+          ranking:
+            max_stars: 50
+            description: Stall
+            performances: []
+    ";
+
+    /// A match of alice and bob in the mode, with both of them joined on one socket each.
+    async fn joined(
+        mode: &str,
+        payloads: &Arc<Mutex<Vec<String>>>,
+    ) -> (Arc<WriteMatchManager>, Vec<rust_socketio::client::Client>) {
+        let (io, url) = serve().await;
+        let tokens = Arc::new(Tokens::new(b"secret", Duration::from_secs(60)));
+        let modes = Modes::parse(MODES).unwrap();
+        let match_manager = create_match(io, &tokens, modes.get(mode).unwrap());
+        let meta = match_manager.get_meta();
+
+        let clients = tokio::task::spawn_blocking({
+            let payloads = payloads.clone();
+            move || {
+                vec![
+                    join(&url, &meta.read, &meta.player_write["alice"], &payloads),
+                    join(&url, &meta.read, &meta.player_write["bob"], &payloads),
+                ]
+            }
+        })
+        .await
+        .unwrap();
+        (match_manager, clients)
+    }
+
+    /// Asserts that the player who had to make the decision was threatened, then warned, and
+    /// then timed out.
+    fn assert_timed_out(payloads: &Mutex<Vec<String>>, decision: &str) {
+        let payloads = payloads.lock().unwrap();
+        let decision = format!("\"decision\":\"{decision}\"");
+        let is = |payload: &String, event: &str| {
+            payload.starts_with(&format!("{event} "))
+                && (event == "timeout" || payload.contains(&decision))
+        };
+        // The position of the first of the events after `from`
+        let after = |from: usize, event: &str| {
+            payloads
+                .iter()
+                .skip(from)
+                .position(|payload| is(payload, event))
+                .map(|position| from + position)
+                .unwrap_or_else(|| panic!("no {event} with {decision} in {payloads:#?}"))
+        };
+
+        let threat = payloads
+            .iter()
+            .rposition(|payload| is(payload, "threaten_timeout"))
+            .unwrap_or_else(|| panic!("no threat with {decision} in {payloads:#?}"));
+        after(after(threat, "timeout_warning"), "timeout");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reconnecting_does_not_stack_deadlines() {
+        let (io, url) = serve().await;
+        let tokens = Arc::new(Tokens::new(b"secret", Duration::from_secs(60)));
+        let modes = Modes::parse(MODES).unwrap();
+        let match_manager = create_match(io, &tokens, modes.get("quick").unwrap());
+        let meta = match_manager.get_meta();
+
+        // alice joins on two sockets, as if she reconnected
+        let alice_payloads = Arc::new(Mutex::new(Vec::new()));
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let clients = tokio::task::spawn_blocking({
+            let alice_payloads = alice_payloads.clone();
+            let payloads = payloads.clone();
+            move || {
+                let alice = &meta.player_write["alice"];
+                let bob = &meta.player_write["bob"];
+                vec![
+                    join(&url, &meta.read, alice, &alice_payloads),
+                    join(&url, &meta.read, alice, &payloads),
+                    join(&url, &meta.read, bob, &payloads),
+                ]
+            }
+        })
+        .await
+        .unwrap();
+
+        // They cut and draw on the engine, but then no one moves, so the server moves for
+        // them, but only once per decision
+        let instance = match_manager.get_match();
+        assert!(play_until(&instance, |instance| instance.trump_suit().is_some()).await);
+        tokio::time::sleep(Duration::from_millis(3500)).await;
+        assert!(received(&payloads, "automated_move") > 0);
+        assert_eq!(
+            received(&alice_payloads, "threaten_timeout"),
+            received(&alice_payloads, "allow_play_card")
+                + received(&alice_payloads, "allow_cutt_deck")
+                + received(&alice_payloads, "allow_draw_card")
+        );
+        assert_eq!(received(&payloads, "timeout"), 0, "{:#?}", payloads.lock().unwrap());
+
+        disconnect(clients).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalling_on_the_cut_times_out() {
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let (_match_manager, clients) = joined("stall", &payloads).await;
+
+        tokio::time::sleep(Duration::from_millis(3500)).await;
+        assert_timed_out(&payloads, "cut");
+
+        disconnect(clients).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalling_on_the_draw_times_out() {
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let (match_manager, clients) = joined("stall", &payloads).await;
+
+        let instance = match_manager.get_match();
+        let cut = |instance: &schnapsen_rs::SchnapsenDuo| instance.opening() == Some(Opening::Draw);
+        assert!(play_until(&instance, cut).await);
+        tokio::time::sleep(Duration::from_millis(3500)).await;
+        assert_timed_out(&payloads, "take_cards");
+
+        disconnect(clients).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalling_on_the_cut_of_the_next_round_times_out() {
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let (match_manager, clients) = joined("stall_bummerl", &payloads).await;
+
+        // The first round is played, the winner then has to cut for the next one
+        let instance = match_manager.get_match();
+        assert!(play_until(&instance, |instance| instance.round_outcome().is_some()).await);
+        tokio::time::sleep(Duration::from_millis(4500)).await;
+        assert_eq!(instance.lock().unwrap().opening(), Some(Opening::Cut));
+        assert_timed_out(&payloads, "cut");
+
+        disconnect(clients).await;
+    }
+}
+
+mod naming {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::helpers::{create_match, disconnect, join, play_until, serve};
+    use crate::{modes::Modes, tokens::Tokens};
+
+    const MODES: &str = "
+        german:
+          variant: duo
+          length: round
+          naming: German
+          ranking:
+            max_stars: 50
+            description: German
+            performances: []
+    ";
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cards_are_sent_with_the_naming_of_the_mode() {
+        let (io, url) = serve().await;
+        let tokens = Arc::new(Tokens::new(b"secret", Duration::from_secs(60)));
+        let modes = Modes::parse(MODES).unwrap();
+        let match_manager = create_match(io, &tokens, modes.get("german").unwrap());
+        let meta = match_manager.get_meta();
+
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let clients = tokio::task::spawn_blocking({
+            let payloads = payloads.clone();
+            move || {
+                vec![
+                    join(&url, &meta.read, &meta.player_write["alice"], &payloads),
+                    join(&url, &meta.read, &meta.player_write["bob"], &payloads),
+                ]
+            }
+        })
+        .await
+        .unwrap();
+
+        let instance = match_manager.get_match();
+        assert!(play_until(&instance, |instance| instance.trump_suit().is_some()).await);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        {
+            let payloads = payloads.lock().unwrap();
+            let cards = payloads
+                .iter()
+                .filter(|payload| payload.contains("\"suit\""))
+                .count();
+            assert!(cards >= 10, "{payloads:#?}");
+            for payload in payloads.iter() {
+                for french in ["Hearts", "Diamonds", "Clubs", "Spades", "Jack", "Queen"] {
+                    assert!(!payload.contains(french), "{payload}");
+                }
+            }
+        }
+
+        disconnect(clients).await;
+    }
+}
+
+mod performances {
+    use std::collections::HashMap;
+
+    use schnapsen_rs::PublicEvent;
+
+    use crate::{match_manager::Performances, modes::Modes};
+
+    const MODES: &str = "
+        ranked:
+          variant: duo
+          length: bummerl
+          ranking:
+            max_stars: 50
+            description: Ranked
+            performances:
+              - name: schwarz
+                weight: 2
+              - name: forty
+                weight: 1
+    ";
+
+    fn result(winner: &str, points: u8) -> PublicEvent {
+        PublicEvent::Result {
+            winner: winner.to_string(),
+            points,
+            ranked: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn rankings_have_the_performances_of_the_mode() {
+        let modes = Modes::parse(MODES).unwrap();
+        let mode = modes.get("ranked").unwrap();
+        let mut performances = Performances::new(&mode.ranking);
+
+        performances.on_event(&result("alice", 3));
+        performances.on_event(&result("alice", 3));
+        performances.on_event(&result("bob", 2));
+        performances.on_event(&PublicEvent::Announce {
+            user_id: "bob".to_string(),
+            announcement: "40 H".parse().unwrap(),
+        });
+        performances.on_event(&PublicEvent::CloseTalon {
+            user_id: "bob".to_string(),
+        });
+
+        let ranking = performances.ranking();
+        assert_eq!(ranking.performances.len(), 2);
+        assert_eq!(ranking.performances["schwarz"], ["alice"]);
+        assert_eq!(ranking.performances["forty"], ["bob"]);
+        assert_eq!(mode.next_round_delay.as_secs(), 5);
+    }
+}
+
+mod teardown {
+    use std::{sync::Arc, time::Duration};
+
+    use socketioxide::SocketIo;
+
+    use crate::{
+        match_manager::{WriteMatchManager, TEARDOWN_GRACE},
+        modes::Modes,
+        registry::MatchRegistry,
+        tokens::Tokens,
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn aborted_matches_let_go_of_everything() {
+        std::env::set_var("PUBLIC_ADDR", "localhost");
+        std::env::set_var("PRIVATE_ADDR", "localhost");
+        std::env::set_var("REGION", "local");
+        let io = Arc::new(SocketIo::new_layer().1);
+        let tokens = Arc::new(Tokens::new(b"secret", Duration::from_secs(60)));
+        let registry = Arc::new(MatchRegistry::new());
+        let modes = Modes::load();
+
+        let modes: Vec<_> = modes.iter().collect();
+        let mut matches = Vec::new();
+        for &(mode, config) in modes.iter().cycle().take(50) {
+            let new_match = gn_communicator::models::CreateMatch {
+                game: "Schnapsen".to_string(),
+                mode: mode.clone(),
+                players: vec!["alice".to_string(), "bob".to_string()],
+                ai_players: vec![],
+            };
+            let match_manager = registry.create(|match_id| {
+                WriteMatchManager::create(
+                    io.clone(),
+                    match_id,
+                    new_match,
+                    config,
+                    2,
+                    tokens.clone(),
+                )
+            });
+            let namespace = format!("/{}", match_manager.get_meta().read);
+            assert!(io.of(namespace.as_str()).is_some());
+            matches.push((
+                namespace,
+                Arc::downgrade(&match_manager),
+                Arc::downgrade(&match_manager.get_match()),
+            ));
+            match_manager.abort("Torn down".to_string());
+        }
+
+        assert!(registry.all().is_empty());
+        tokio::time::sleep(Duration::from_secs(TEARDOWN_GRACE + 1)).await;
+        for (namespace, match_manager, instance) in matches {
+            assert!(io.of(namespace.as_str()).is_none(), "{namespace} is still served");
+            assert_eq!(match_manager.strong_count(), 0, "{namespace} is still held");
+            assert_eq!(instance.strong_count(), 0, "the engine of {namespace} is still held");
+        }
+    }
+}
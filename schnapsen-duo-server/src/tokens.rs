@@ -0,0 +1,128 @@
+//! Write tokens, which let a player play their seat of a match until they expire. They are signed
+//! with the server's secret, so they can be checked without asking anyone. Only whether a token was
+//! revoked is up to the match.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use tracing::warn;
+
+/// How long tokens are valid if `TOKEN_TTL` isn't set, longer than any match should take.
+const DEFAULT_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// What a token grants.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Claims {
+    #[serde(rename = "mid")]
+    pub match_id: String,
+    #[serde(rename = "pid")]
+    pub player_id: String,
+    /// Unix time in seconds from which the token is refused.
+    #[serde(rename = "exp")]
+    pub expires: u64,
+    /// The player's tokens of earlier generations were revoked.
+    #[serde(rename = "gen")]
+    pub generation: u32,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TokenError {
+    #[error("Malformed token")]
+    Malformed,
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Token expired")]
+    Expired,
+}
+
+/// Issues and checks the tokens of all matches of the server.
+pub struct Tokens {
+    secret: Vec<u8>,
+    ttl: Duration,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl Tokens {
+    pub fn new(secret: &[u8], ttl: Duration) -> Self {
+        Self {
+            secret: secret.to_vec(),
+            ttl,
+        }
+    }
+
+    /// Signs with the secret in `TOKEN_SECRET`, tokens are valid for `TOKEN_TTL` seconds. Without
+    /// a secret, a random one is drawn, so tokens don't survive a restart of the server.
+    pub fn from_env() -> Self {
+        let secret = match std::env::var("TOKEN_SECRET") {
+            Ok(secret) if !secret.is_empty() => secret.into_bytes(),
+            _ => {
+                warn!("No TOKEN_SECRET set, tokens are only valid until the server restarts");
+                let mut secret = vec![0; 32];
+                OsRng.fill_bytes(&mut secret);
+                secret
+            }
+        };
+        let ttl = match std::env::var("TOKEN_TTL") {
+            Ok(ttl) if !ttl.is_empty() => {
+                Duration::from_secs(ttl.parse().expect("TOKEN_TTL must be a number of seconds"))
+            }
+            _ => DEFAULT_TTL,
+        };
+        Self::new(&secret, ttl)
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        Hmac::new_from_slice(&self.secret).expect("HMAC takes keys of any size")
+    }
+
+    /// A token for the player to play their seat of the match, from now until the TTL is over.
+    pub fn issue(&self, match_id: &str, player_id: &str, generation: u32) -> String {
+        let claims = Claims {
+            match_id: match_id.to_string(),
+            player_id: player_id.to_string(),
+            expires: now() + self.ttl.as_secs(),
+            generation,
+        };
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap());
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+        format!("{payload}.{signature}")
+    }
+
+    /// What the token grants, if it was signed with the secret and hasn't expired yet.
+    pub fn verify(&self, token: &str) -> Result<Claims, TokenError> {
+        let (payload, signature) = token.split_once('.').ok_or(TokenError::Malformed)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| TokenError::Malformed)?;
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| TokenError::InvalidSignature)?;
+
+        let claims: Claims = URL_SAFE_NO_PAD
+            .decode(payload)
+            .ok()
+            .and_then(|payload| serde_json::from_slice(&payload).ok())
+            .ok_or(TokenError::Malformed)?;
+        if now() >= claims.expires {
+            return Err(TokenError::Expired);
+        }
+        Ok(claims)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::models::Action;
+
+use super::{playable_cards, Agent, Observation};
+
+/// Follows the rules and nothing more: plays the weakest playable card and never announces,
+/// swaps the trump or closes the talon.
+#[derive(Default)]
+pub struct BeginnerAgent;
+
+impl BeginnerAgent {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Agent for BeginnerAgent {
+    fn act(&mut self, _observation: &Observation, legal: &[Action]) -> Action {
+        playable_cards(legal)
+            .iter()
+            .min_by_key(|card| (card.points(), card.index()))
+            .map(Action::PlayCard)
+            .unwrap_or(legal[0])
+    }
+}
@@ -0,0 +1,103 @@
+use crate::models::{Action, Card, CardSet, CardVal};
+
+use super::{playable_cards, Agent, Observation};
+
+/// Announces whenever possible, wins tricks as cheap as possible and saves its trumps for tricks
+/// which are worth it.
+#[derive(Default)]
+pub struct GreedyAgent;
+
+impl GreedyAgent {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The card which costs the least to give away. Queens and kings which could still be
+    /// announced are treated as more valuable than they are.
+    fn cheapest(observation: &Observation, cards: CardSet) -> Option<Card> {
+        let marriages = CardSet::value(CardVal::Queen) | CardSet::value(CardVal::King);
+        let announced: CardSet = observation
+            .announcements
+            .iter()
+            .flat_map(|announcement| announcement.cards)
+            .collect();
+
+        cards.iter().min_by_key(|card| {
+            let partner = Card::new(
+                if card.value == CardVal::Queen {
+                    CardVal::King
+                } else {
+                    CardVal::Queen
+                },
+                card.suit,
+            );
+            let keeps_marriage = marriages.contains(*card)
+                && observation.hand.contains(partner)
+                && !announced.contains(*card);
+            (
+                card.points() + if keeps_marriage { 10 } else { 0 },
+                card.index(),
+            )
+        })
+    }
+
+    fn respond(observation: &Observation, lead: Card, playable: CardSet) -> Card {
+        let trumps = CardSet::suit(observation.trump);
+        let winners: CardSet = playable
+            .iter()
+            .filter(|card| card.beats(&lead, observation.trump))
+            .collect();
+
+        if let Some(card) = Self::cheapest(observation, winners - trumps) {
+            return card;
+        }
+
+        let cheapest_trump = Self::cheapest(observation, winners & trumps);
+        if let Some(trump) = cheapest_trump {
+            let worth_it = lead.points() >= CardVal::Ten as u8
+                || observation.is_talon_exhausted()
+                || observation.points + lead.points() + trump.points() >= 66;
+            if worth_it {
+                return trump;
+            }
+        }
+
+        Self::cheapest(observation, playable - trumps)
+            .or(Self::cheapest(observation, playable))
+            .unwrap()
+    }
+
+    fn lead(observation: &Observation, playable: CardSet) -> Card {
+        let trumps = CardSet::suit(observation.trump);
+        Self::cheapest(observation, playable - trumps)
+            .or(Self::cheapest(observation, playable))
+            .unwrap()
+    }
+}
+
+impl Agent for GreedyAgent {
+    fn act(&mut self, observation: &Observation, legal: &[Action]) -> Action {
+        let preferred = legal
+            .iter()
+            .find(|action| matches!(action, Action::Announce40))
+            .or(legal
+                .iter()
+                .find(|action| matches!(action, Action::Announce20(_))))
+            .or(legal
+                .iter()
+                .find(|action| matches!(action, Action::SwapTrump(_))));
+        if let Some(action) = preferred {
+            return *action;
+        }
+
+        let playable = playable_cards(legal);
+        if playable.is_empty() {
+            return legal[0];
+        }
+
+        Action::PlayCard(match observation.lead {
+            Some(lead) => Self::respond(observation, lead, playable),
+            None => Self::lead(observation, playable),
+        })
+    }
+}
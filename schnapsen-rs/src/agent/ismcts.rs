@@ -0,0 +1,231 @@
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    models::Action,
+    solver::Solver,
+    state::{GameState, RoundResult},
+};
+
+use super::{Agent, GreedyAgent, Observation};
+
+/// How long the search of a single decision runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsmctsConfig {
+    pub budget: Budget,
+    /// The exploration constant of the UCB formula.
+    pub exploration: f64,
+}
+
+impl Default for IsmctsConfig {
+    fn default() -> Self {
+        Self {
+            budget: Budget::Iterations(2000),
+            exploration: 0.7,
+        }
+    }
+}
+
+struct Node {
+    action: Option<Action>,
+    /// The player who took `action`.
+    player: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    visits: u32,
+    /// How often this node could have been selected.
+    availability: u32,
+    reward: f64,
+}
+
+impl Node {
+    fn new(action: Option<Action>, player: usize, parent: Option<usize>) -> Self {
+        Self {
+            action,
+            player,
+            parent,
+            children: Vec::new(),
+            visits: 0,
+            availability: 1,
+            reward: 0.0,
+        }
+    }
+}
+
+/// Information-set Monte Carlo tree search. Every iteration samples the opponent's hand and the
+/// talon consistent with the observation and walks a single tree shared between all samples.
+/// Once the talon is exhausted, every card is known and the rest of the round is solved exactly.
+pub struct IsmctsAgent {
+    config: IsmctsConfig,
+    rng: StdRng,
+    solver: Solver,
+}
+
+impl IsmctsAgent {
+    pub fn new(config: IsmctsConfig) -> Self {
+        Self {
+            config,
+            rng: StdRng::from_entropy(),
+            solver: Solver::new(),
+        }
+    }
+
+    pub fn seeded(config: IsmctsConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            solver: Solver::new(),
+        }
+    }
+
+    /// Rewards lie within `0..=1`, where winning more game points is worth more.
+    #[inline]
+    fn reward(result: RoundResult, player: usize) -> f64 {
+        let margin = result.game_points as f64 / 6.0;
+        if result.winner == player {
+            0.5 + margin
+        } else {
+            0.5 - margin
+        }
+    }
+
+    /// Plays random cards, but never misses an announcement or a trump swap.
+    fn rollout(&mut self, state: &mut GameState) {
+        while !state.is_terminal() {
+            let legal = state.legal_actions();
+            let action = legal
+                .iter()
+                .find(|action| {
+                    matches!(
+                        action,
+                        Action::Announce40 | Action::Announce20(_) | Action::SwapTrump(_)
+                    )
+                })
+                .copied()
+                .unwrap_or_else(|| {
+                    let cards: Vec<_> = legal
+                        .iter()
+                        .filter(|action| matches!(action, Action::PlayCard(_)))
+                        .collect();
+                    **cards.choose(&mut self.rng).unwrap()
+                });
+            state.apply(action).unwrap();
+        }
+    }
+
+    fn search(&mut self, observation: &Observation, legal: &[Action]) -> Option<Action> {
+        let mut tree = vec![Node::new(None, 1, None)];
+        let start = Instant::now();
+        let mut iterations = 0;
+
+        loop {
+            match self.config.budget {
+                Budget::Iterations(max) if iterations >= max => break,
+                Budget::Time(max) if start.elapsed() >= max => break,
+                _ => {}
+            }
+            iterations += 1;
+
+            let mut state = GameState::sample(observation, &mut self.rng);
+            let mut node = 0;
+
+            while !state.is_terminal() {
+                let mut available = state.legal_actions();
+                if node == 0 {
+                    available.retain(|action| legal.contains(action));
+                }
+                if available.is_empty() {
+                    return None;
+                }
+
+                let untried: Vec<_> = available
+                    .iter()
+                    .filter(|action| {
+                        !tree[node]
+                            .children
+                            .iter()
+                            .any(|child| tree[*child].action.as_ref() == Some(action))
+                    })
+                    .copied()
+                    .collect();
+
+                if let Some(action) = untried.choose(&mut self.rng).copied() {
+                    let child = tree.len();
+                    tree.push(Node::new(Some(action), state.to_move, Some(node)));
+                    tree[node].children.push(child);
+                    state.apply(action).unwrap();
+                    node = child;
+                    break;
+                }
+
+                let mut best = None;
+                let mut best_score = f64::NEG_INFINITY;
+                for idx in tree[node].children.clone() {
+                    if !available.contains(&tree[idx].action.unwrap()) {
+                        continue;
+                    }
+                    let child = &mut tree[idx];
+                    child.availability += 1;
+                    let score = child.reward / child.visits as f64
+                        + self.config.exploration
+                            * ((child.availability as f64).ln() / child.visits as f64).sqrt();
+                    if score > best_score {
+                        best_score = score;
+                        best = Some(idx);
+                    }
+                }
+                let best = best.unwrap();
+                state.apply(tree[best].action.unwrap()).unwrap();
+                node = best;
+            }
+
+            self.rollout(&mut state);
+            let result = state.result.unwrap();
+
+            let mut current = Some(node);
+            while let Some(idx) = current {
+                let node = &mut tree[idx];
+                node.visits += 1;
+                node.reward += Self::reward(result, node.player);
+                current = node.parent;
+            }
+        }
+
+        tree[0]
+            .children
+            .iter()
+            .max_by_key(|child| tree[**child].visits)
+            .and_then(|child| tree[*child].action)
+    }
+}
+
+impl Default for IsmctsAgent {
+    fn default() -> Self {
+        Self::new(IsmctsConfig::default())
+    }
+}
+
+impl Agent for IsmctsAgent {
+    fn act(&mut self, observation: &Observation, legal: &[Action]) -> Action {
+        if legal.len() == 1 {
+            return legal[0];
+        }
+        if observation.deck_size == 0 && observation.trump_card.is_none() {
+            let state = GameState::sample(observation, &mut self.rng);
+            if let Some(solution) = self.solver.solve(&state) {
+                if legal.contains(&solution.action) {
+                    return solution.action;
+                }
+            }
+        }
+        self.search(observation, legal)
+            .unwrap_or_else(|| GreedyAgent::new().act(observation, legal))
+    }
+}
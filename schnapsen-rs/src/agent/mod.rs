@@ -0,0 +1,147 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::SchnapsenDuoClient,
+    models::{Action, Announcement, Card, CardSet, CardSuit},
+    PlayerError, RoundOutcome, SchnapsenDuo,
+};
+
+mod beginner;
+mod greedy;
+mod ismcts;
+mod persona;
+mod random;
+
+pub use beginner::BeginnerAgent;
+pub use greedy::GreedyAgent;
+pub use ismcts::{Budget, IsmctsAgent, IsmctsConfig};
+pub use persona::{Persona, PERSONAS};
+pub use random::RandomAgent;
+
+/// A bot which decides on one of the legal actions of a player.
+pub trait Agent: Send {
+    /// `legal` is never empty and every returned action has to be part of it.
+    fn act(&mut self, observation: &Observation, legal: &[Action]) -> Action;
+}
+
+/// The names understood by `by_name`.
+pub const AGENT_NAMES: &str = "random, greedy, beginner, ismcts, ismcts:<iterations>";
+
+/// Creates one of the bundled agents, where `ismcts:<iterations>` limits the search to a number of
+/// iterations. Randomized agents are seeded with `seed`.
+pub fn by_name(name: &str, seed: u64) -> Option<Box<dyn Agent>> {
+    let agent: Box<dyn Agent> = match name.split_once(':') {
+        None if name == "random" => Box::new(RandomAgent::seeded(seed)),
+        None if name == "greedy" => Box::new(GreedyAgent::new()),
+        None if name == "beginner" => Box::new(BeginnerAgent::new()),
+        None if name == "ismcts" => Box::new(IsmctsAgent::seeded(IsmctsConfig::default(), seed)),
+        Some(("ismcts", iterations)) => {
+            let config = IsmctsConfig {
+                budget: Budget::Iterations(iterations.parse().ok()?),
+                ..Default::default()
+            };
+            Box::new(IsmctsAgent::seeded(config, seed))
+        }
+        _ => return None,
+    };
+    Some(agent)
+}
+
+/// The cards of all `Action::PlayCard` actions.
+pub fn playable_cards(legal: &[Action]) -> CardSet {
+    legal
+        .iter()
+        .filter_map(|action| match action {
+            Action::PlayCard(card) => Some(*card),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Everything a single player knows about the current round.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Observation {
+    pub player_id: String,
+    pub hand: CardSet,
+    pub playable: CardSet,
+    pub trump: CardSuit,
+    /// The open card below the talon, until it is taken.
+    pub trump_card: Option<Card>,
+    /// Number of face-down cards left in the talon.
+    pub deck_size: usize,
+    pub closed_talon: Option<String>,
+    /// The card the opponent led in the current trick.
+    pub lead: Option<Card>,
+    pub won: CardSet,
+    pub opponent_won: CardSet,
+    pub points: u8,
+    pub opponent_points: u8,
+    pub announcements: Vec<Announcement>,
+    pub opponent_announcements: Vec<Announcement>,
+    pub opponent_hand_size: usize,
+    /// Cards known to be in the opponent's hand unless played since, besides their announcements.
+    pub opponent_revealed: CardSet,
+    /// Cards known not to be in the opponent's hand.
+    pub opponent_ruled_out: CardSet,
+}
+
+impl Observation {
+    /// Whether the rules of the closed talon apply, because it was either closed or drawn empty.
+    #[inline]
+    pub fn is_talon_exhausted(&self) -> bool {
+        self.closed_talon.is_some() || self.trump_card.is_none()
+    }
+
+    /// All cards which are neither in the own hand nor already played.
+    #[inline]
+    pub fn unseen(&self) -> CardSet {
+        let mut unseen = !(self.hand | self.won | self.opponent_won);
+        if let Some(card) = self.trump_card {
+            unseen.remove(card);
+        }
+        if let Some(card) = self.lead {
+            unseen.remove(card);
+        }
+        unseen
+    }
+}
+
+/// Plays the current round of an already dealt game between two agents, where the agent at index
+/// `i` plays for the player at index `i`.
+pub fn play_round(
+    instance: Arc<Mutex<SchnapsenDuo>>,
+    agents: [&mut dyn Agent; 2],
+) -> Result<RoundOutcome, PlayerError> {
+    let players = instance.lock().unwrap().players.clone();
+    let clients = players.map(|player| SchnapsenDuoClient::new(player, instance.clone()));
+
+    let active_id = || {
+        instance
+            .lock()
+            .unwrap()
+            .active
+            .as_ref()
+            .map(|active| active.read().unwrap().id.clone())
+    };
+    if active_id().is_none() {
+        return Err(PlayerError::CardsNotDealt);
+    }
+
+    while let Some(active) = active_id() {
+        let idx = clients
+            .iter()
+            .position(|client| client.get_player_id() == active)
+            .unwrap();
+        let client = &clients[idx];
+
+        let legal = client.legal_actions();
+        let observation = client.observe().ok_or(PlayerError::CardsNotDealt)?;
+        let action = agents[idx].act(&observation, &legal);
+        client.perform(action)?;
+    }
+
+    let outcome = instance.lock().unwrap().round_outcome().cloned();
+    outcome.ok_or(PlayerError::CardsNotDealt)
+}
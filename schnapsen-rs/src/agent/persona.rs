@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use super::{by_name, Agent};
+
+/// An AI player as the matchmaker knows it, and the agent behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Persona {
+    /// Also the id the AI player gets in a match.
+    pub display_name: &'static str,
+    pub elo: u32,
+    /// One of the names understood by `by_name`.
+    pub agent: &'static str,
+    /// How long the persona takes for a move, so it doesn't play faster than a human could follow.
+    pub think: Duration,
+}
+
+pub const PERSONAS: [Persona; 3] = [
+    Persona {
+        display_name: "Bugo Hoss",
+        elo: 250,
+        agent: "beginner",
+        think: Duration::from_millis(1300),
+    },
+    Persona {
+        display_name: "Lalph Raulen",
+        elo: 500,
+        agent: "greedy",
+        think: Duration::from_millis(1000),
+    },
+    Persona {
+        display_name: "Kolfgang Woscher",
+        elo: 1500,
+        agent: "ismcts",
+        think: Duration::from_millis(500),
+    },
+];
+
+impl Persona {
+    pub fn find(display_name: &str) -> Option<&'static Persona> {
+        PERSONAS
+            .iter()
+            .find(|persona| persona.display_name == display_name)
+    }
+
+    pub fn agent(&self, seed: u64) -> Box<dyn Agent> {
+        by_name(self.agent, seed)
+            .unwrap_or_else(|| panic!("Unknown agent {} of {}", self.agent, self.display_name))
+    }
+}
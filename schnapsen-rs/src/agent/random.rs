@@ -0,0 +1,36 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::models::Action;
+
+use super::{Agent, Observation};
+
+/// Picks any of the legal actions with equal probability.
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl RandomAgent {
+    pub fn new() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Agent for RandomAgent {
+    fn act(&mut self, _observation: &Observation, legal: &[Action]) -> Action {
+        *legal.choose(&mut self.rng).unwrap()
+    }
+}
@@ -0,0 +1,244 @@
+//! Plays a bummerl in the terminal against one of the bundled agents, without a server.
+
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    process::ExitCode,
+    sync::{Arc, Mutex},
+};
+
+use schnapsen_rs::{
+    agent::{self, Agent, AGENT_NAMES},
+    client::SchnapsenDuoClient,
+    models::{Action, Card},
+    SchnapsenDuo,
+};
+
+const USAGE: &str = "usage: schnapsen-cli [agent] [--seed <n>]";
+
+const HELP: &str = "\
+commands:
+  play <card>   play a card, like `play HA`, `play ♥A` or `play Herz Ass`
+  20 <suit>     announce a marriage, like `20 H`
+  40            announce the marriage of trump
+  close         close the talon
+  swap          swap the jack of trump for the trump card
+  help          show this help
+  quit          give up";
+
+const YOU: &str = "you";
+const BOT: &str = "bot";
+
+/// Game points needed to win a bummerl.
+const BUMMERL_POINTS: u8 = 7;
+
+struct Game {
+    instance: Arc<Mutex<SchnapsenDuo>>,
+    you: SchnapsenDuoClient,
+    bot: SchnapsenDuoClient,
+    agent: Box<dyn Agent>,
+}
+
+impl Game {
+    fn new(agent: Box<dyn Agent>, seed: u64) -> Self {
+        let mut instance = SchnapsenDuo::seeded(&[YOU.to_string(), BOT.to_string()], seed);
+        let you = instance.get_player(YOU).unwrap();
+        let bot = instance.get_player(BOT).unwrap();
+        instance.set_active_player(you.clone()).unwrap();
+        instance.distribute_cards().unwrap();
+
+        let instance = Arc::new(Mutex::new(instance));
+        Self {
+            you: SchnapsenDuoClient::new(you, instance.clone()),
+            bot: SchnapsenDuoClient::new(bot, instance.clone()),
+            instance,
+            agent,
+        }
+    }
+
+    fn active(&self) -> Option<String> {
+        let active = self.instance.lock().unwrap().get_active_player()?;
+        let id = active.read().unwrap().id.clone();
+        Some(id)
+    }
+
+    /// The tricks taken by each player in the current round.
+    fn tricks(&self) -> [Vec<[Card; 2]>; 2] {
+        [YOU, BOT].map(|id| {
+            let player = self.instance.lock().unwrap().get_player(id).unwrap();
+            let tricks = player.read().unwrap().tricks.clone();
+            tricks
+        })
+    }
+
+    fn game_points(&self, id: &str) -> u8 {
+        let player = self.instance.lock().unwrap().get_player(id).unwrap();
+        let points = player.read().unwrap().points;
+        points
+    }
+
+    fn show(&self, legal: &[Action]) {
+        let Some(observation) = self.you.observe() else {
+            return;
+        };
+
+        let talon = match (&observation.trump_card, &observation.closed_talon) {
+            (_, Some(closer)) => format!("closed by {closer}"),
+            (Some(card), None) => format!("{card:#} below {} cards", observation.deck_size),
+            (None, None) => "empty".to_string(),
+        };
+        println!();
+        println!("trump  {:#}, talon {talon}", observation.trump);
+        if let Some(lead) = observation.lead {
+            println!("trick  {BOT} led {lead:#}");
+        }
+        let hand: Vec<_> = observation
+            .hand
+            .iter()
+            .map(|card| format!("{card:#}"))
+            .collect();
+        println!("hand   {}", hand.join(" "));
+        println!(
+            "points {YOU} {}, {BOT} {}",
+            observation.points, observation.opponent_points
+        );
+        let legal: Vec<_> = legal.iter().map(|action| format!("{action}")).collect();
+        println!("legal  {}", legal.join(", "));
+    }
+
+    /// Reports the trick finished since `before`.
+    fn report_trick(&self, before: &[Vec<[Card; 2]>; 2]) {
+        for (id, (now, before)) in [YOU, BOT].iter().zip(self.tricks().iter().zip(before)) {
+            // Tricks keep the response first
+            if let Some([response, lead]) = now.get(before.len()) {
+                println!("{id} took the trick {lead:#} {response:#}");
+            }
+        }
+    }
+
+    fn bot_turn(&mut self) -> Result<(), String> {
+        let legal = self.bot.legal_actions();
+        let observation = self.bot.observe().ok_or("the cards are not dealt")?;
+        let action = self.agent.act(&observation, &legal);
+        println!("{BOT} {action}");
+        self.bot
+            .perform(action)
+            .map_err(|err| err.get_message().to_string())
+    }
+
+    /// Returns `false` once the player wants to quit.
+    fn your_turn(&mut self, lines: &mut impl Iterator<Item = io::Result<String>>) -> bool {
+        let legal = self.you.legal_actions();
+        self.show(&legal);
+
+        loop {
+            print!("> ");
+            io::stdout().flush().unwrap();
+            let Some(Ok(line)) = lines.next() else {
+                return false;
+            };
+
+            let action = match line.trim() {
+                "" => continue,
+                "quit" | "exit" => return false,
+                "help" => {
+                    println!("{HELP}");
+                    continue;
+                }
+                "swap" => legal
+                    .iter()
+                    .find(|action| matches!(action, Action::SwapTrump(_)))
+                    .copied()
+                    .ok_or_else(|| "You don't hold the jack of trump".to_string()),
+                command => command.parse::<Action>().map_err(|err| err.to_string()),
+            };
+
+            match action.and_then(|action| {
+                self.you
+                    .perform(action)
+                    .map_err(|err| err.get_message().to_string())
+            }) {
+                Ok(()) => return true,
+                Err(err) => println!("{err}"),
+            }
+        }
+    }
+
+    fn run(&mut self) -> Result<(), String> {
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        println!("{HELP}");
+
+        loop {
+            let tricks = self.tricks();
+            match self.active().as_deref() {
+                Some(YOU) => {
+                    if !self.your_turn(&mut lines) {
+                        return Ok(());
+                    }
+                }
+                Some(_) => self.bot_turn()?,
+                None => {
+                    let outcome = self.instance.lock().unwrap().round_outcome().cloned();
+                    let outcome = outcome.ok_or("no player is active")?;
+                    println!(
+                        "\n{} won the round and {} game points, {YOU} {} : {} {BOT}",
+                        outcome.winner,
+                        outcome.points,
+                        self.game_points(YOU),
+                        self.game_points(BOT)
+                    );
+                    if self.game_points(&outcome.winner) >= BUMMERL_POINTS {
+                        println!("{} won the bummerl", outcome.winner);
+                        return Ok(());
+                    }
+
+                    let winner = self.instance.lock().unwrap().get_player(&outcome.winner);
+                    self.instance
+                        .lock()
+                        .unwrap()
+                        .next_round(winner.ok_or("unknown winner")?);
+                    continue;
+                }
+            }
+            self.report_trick(&tricks);
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let mut name = "greedy".to_string();
+    let mut seed = rand::random();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => match args.next().and_then(|seed| seed.parse().ok()) {
+                Some(value) => seed = value,
+                None => {
+                    eprintln!("--seed needs a number\n{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "-h" | "--help" => {
+                eprintln!("{USAGE}\nagents: {AGENT_NAMES}");
+                return ExitCode::SUCCESS;
+            }
+            _ => name = arg,
+        }
+    }
+
+    let Some(agent) = agent::by_name(&name, seed) else {
+        eprintln!("unknown agent {name}\n{USAGE}\nagents: {AGENT_NAMES}");
+        return ExitCode::FAILURE;
+    };
+
+    println!("playing against {name} with seed {seed}");
+    match Game::new(agent, seed).run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
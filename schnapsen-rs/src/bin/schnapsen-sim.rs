@@ -0,0 +1,206 @@
+//! Pits two agents against each other for a number of seeded rounds or bummerls, without a server.
+//! A summary is printed to stderr, the statistics are written as JSON to stdout or `--output`.
+
+use std::{
+    env, fs,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+};
+
+use schnapsen_rs::{
+    agent::{self, play_round, Agent, AGENT_NAMES},
+    stats::{Report, Statistics},
+    SchnapsenDuo,
+};
+
+const USAGE: &str = "\
+usage: schnapsen-sim <agent> <agent> [--rounds <n> | --bummerls <n>] [--seed <n>] [--output <file>]
+
+the agents take turns in leading the first round, every round or bummerl has its own seed";
+
+/// Game points needed to win a bummerl.
+const BUMMERL_POINTS: u8 = 7;
+
+/// The ids of the engine players, which are the indices of the agents.
+const IDS: [&str; 2] = Statistics::IDS;
+
+#[derive(Debug)]
+struct Options {
+    agents: [String; 2],
+    count: usize,
+    bummerls: bool,
+    seed: u64,
+    output: Option<String>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut agents = Vec::new();
+        let mut options = Options {
+            agents: Default::default(),
+            count: 1000,
+            bummerls: false,
+            seed: 0,
+            output: None,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or(format!("{name} needs a value"));
+            match arg.as_str() {
+                "--rounds" | "--bummerls" => {
+                    options.bummerls = arg == "--bummerls";
+                    options.count = value(&arg)?
+                        .parse()
+                        .map_err(|_| format!("{arg} needs a number"))?;
+                }
+                "--seed" => {
+                    options.seed = value(&arg)?
+                        .parse()
+                        .map_err(|_| "--seed needs a number".to_string())?;
+                }
+                "--output" => options.output = Some(value(&arg)?),
+                "-h" | "--help" => return Err(String::new()),
+                _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+                _ => agents.push(arg),
+            }
+        }
+
+        options.agents = agents
+            .try_into()
+            .map_err(|_| "exactly two agents are needed".to_string())?;
+        Ok(options)
+    }
+}
+
+fn create_agent(name: &str, seed: u64) -> Result<Box<dyn Agent>, String> {
+    agent::by_name(name, seed).ok_or_else(|| format!("unknown agent {name}"))
+}
+
+struct Simulation {
+    agents: [Box<dyn Agent>; 2],
+    statistics: Statistics,
+}
+
+impl Simulation {
+    /// Plays the dealt round, where `seats[i]` is the index of the agent of the player at `i`.
+    fn play(
+        &mut self,
+        instance: &Arc<Mutex<SchnapsenDuo>>,
+        seats: [usize; 2],
+    ) -> Result<usize, String> {
+        let [first, second] = &mut self.agents;
+        let agents: [&mut dyn Agent; 2] = if seats[0] == 0 {
+            [first.as_mut(), second.as_mut()]
+        } else {
+            [second.as_mut(), first.as_mut()]
+        };
+        let outcome = play_round(instance.clone(), agents).map_err(|err| err.to_string())?;
+
+        let winner = Statistics::agent_of(&outcome.winner).unwrap();
+        self.statistics
+            .count_round(instance.lock().unwrap().record(), winner, outcome.points);
+        Ok(winner)
+    }
+
+    /// A new game where the agent at `leader` plays first.
+    fn deal(seed: u64, leader: usize) -> (Arc<Mutex<SchnapsenDuo>>, [usize; 2]) {
+        let seats = [leader, 1 - leader];
+        let ids = seats.map(|agent| IDS[agent].to_string());
+        let mut instance = SchnapsenDuo::seeded(&ids, seed);
+        let first = instance.get_player(IDS[leader]).unwrap();
+        instance.set_active_player(first).unwrap();
+        instance.distribute_cards().unwrap();
+        (Arc::new(Mutex::new(instance)), seats)
+    }
+
+    fn run_rounds(&mut self, count: usize, seed: u64) -> Result<(), String> {
+        for round in 0..count {
+            let (instance, seats) = Self::deal(seed.wrapping_add(round as u64), round % 2);
+            self.play(&instance, seats)?;
+        }
+        Ok(())
+    }
+
+    fn run_bummerls(&mut self, count: usize, seed: u64) -> Result<(), String> {
+        for bummerl in 0..count {
+            let (instance, seats) = Self::deal(seed.wrapping_add(bummerl as u64), bummerl % 2);
+            loop {
+                let winner = self.play(&instance, seats)?;
+                let player = instance.lock().unwrap().get_player(IDS[winner]).unwrap();
+                if player.read().unwrap().points >= BUMMERL_POINTS {
+                    self.statistics.count_bummerl(winner);
+                    break;
+                }
+                instance.lock().unwrap().next_round(player.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn run(options: &Options) -> Result<Report, String> {
+    let agents = [
+        create_agent(&options.agents[0], options.seed)?,
+        create_agent(&options.agents[1], options.seed.wrapping_add(1))?,
+    ];
+    let mut simulation = Simulation {
+        agents,
+        statistics: Statistics::new(),
+    };
+
+    if options.bummerls {
+        simulation.run_bummerls(options.count, options.seed)?;
+    } else {
+        simulation.run_rounds(options.count, options.seed)?;
+    }
+    Ok(simulation
+        .statistics
+        .report(&options.agents, options.seed, options.bummerls))
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            if !err.is_empty() {
+                eprintln!("{err}");
+            }
+            eprintln!("{USAGE}\nagents: {AGENT_NAMES}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let report = match run(&options) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("simulation failed: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for agent in &report.agents {
+        let rate = agent.bummerl_win_rate.unwrap_or(agent.round_win_rate);
+        eprintln!(
+            "{:<16} won {:5.1}% [{:5.1}%, {:5.1}%], {:.2} game points, {:.2} closes and {:.2} announcements per round",
+            agent.agent,
+            rate.rate * 100.0,
+            rate.lower * 100.0,
+            rate.upper * 100.0,
+            agent.average_game_points,
+            agent.close_talon_rate,
+            agent.announcement_rate,
+        );
+    }
+
+    let json = serde_json::to_string_pretty(&report).unwrap();
+    match &options.output {
+        Some(path) => {
+            if let Err(err) = fs::write(path, json) {
+                eprintln!("couldn't write {path}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+        None => println!("{json}"),
+    }
+    ExitCode::SUCCESS
+}
@@ -1,7 +1,8 @@
 use std::sync::{Arc, Mutex, RwLock};
 
 use crate::{
-    models::{Announcement, Card, Player},
+    agent::Observation,
+    models::{Action, Announcement, Card, Player},
     PlayerError, SchnapsenDuo,
 };
 
@@ -36,6 +37,31 @@ impl SchnapsenDuoClient {
             .is_active(&self.player.read().unwrap())
     }
 
+    #[inline]
+    pub fn observe(&self) -> Option<Observation> {
+        self.instance.lock().unwrap().observe(self.player.clone())
+    }
+
+    #[inline]
+    pub fn legal_actions(&self) -> Vec<Action> {
+        self.instance
+            .lock()
+            .unwrap()
+            .legal_actions(self.player.clone())
+    }
+
+    pub fn perform(&self, action: Action) -> Result<(), crate::PlayerError> {
+        match action {
+            Action::CuttDeck(cards) => self.cutt_deck(cards),
+            Action::TakeCards(idx) => self.take_cards_til(idx),
+            Action::PlayCard(card) => self.play_card(card),
+            Action::SwapTrump(card) => self.swap_trump(card),
+            Action::CloseTalon => self.close_talon(),
+            Action::Announce20(cards) => self.announce_20(cards),
+            Action::Announce40 => self.announce_40(),
+        }
+    }
+
     pub fn draw_card(&self) -> Result<(), crate::PlayerError> {
         let card = self
             .instance
@@ -70,7 +96,7 @@ impl SchnapsenDuoClient {
         self.instance
             .lock()
             .unwrap()
-            .play_card(self.player.clone(), card.clone())?;
+            .play_card(self.player.clone(), card)?;
         Ok(())
     }
 
@@ -79,7 +105,7 @@ impl SchnapsenDuoClient {
             .instance
             .lock()
             .unwrap()
-            .swap_trump(self.player.clone(), card.clone())?;
+            .swap_trump(self.player.clone(), card)?;
 
         self.player.write().unwrap().cards.retain(|x| *x != card);
         self.player.write().unwrap().cards.push(swap);
@@ -122,7 +148,7 @@ impl SchnapsenDuoClient {
             .write()
             .unwrap()
             .announcable
-            .retain(|x| *x == announcement);
+            .retain(|x| x.suit() != announcement.suit());
 
         let mut instance_lock = self.instance.lock().unwrap();
         instance_lock
@@ -0,0 +1,230 @@
+use crate::{
+    models::{Card, CardSet, CardSuit},
+    PrivateEvent, PublicEvent,
+};
+
+/// Where a card is, as far as one player can tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CardLocation {
+    /// In the own hand.
+    Hand,
+    /// Played in a trick, including the current one.
+    Played,
+    /// The face-up trump card below the talon.
+    Trump,
+    /// Certainly in the opponent's hand.
+    Opponent,
+    /// Certainly in the closed talon.
+    Talon,
+    /// Either in the talon or in the opponent's hand.
+    Unknown,
+}
+
+/// The cards the responding player can't hold, as they would have had to play them instead of
+/// `response`. Once no more cards can be drawn, a player has to follow suit or play a trump and
+/// after the talon was closed, they also have to take the trick if they can.
+pub fn ruled_out_by_response(
+    lead: Card,
+    response: Card,
+    trump: CardSuit,
+    closed: bool,
+    exhausted: bool,
+) -> CardSet {
+    let mut ruled_out = CardSet::EMPTY;
+
+    if (closed || exhausted) && response.suit != lead.suit {
+        ruled_out |= CardSet::suit(lead.suit);
+        if response.suit != trump {
+            ruled_out |= CardSet::suit(trump);
+        }
+    }
+
+    if closed && !response.beats(&lead, trump) {
+        ruled_out |= CardSet::suit(lead.suit)
+            .iter()
+            .filter(|card| card.beats(&lead, trump))
+            .collect();
+    }
+    ruled_out
+}
+
+/// Counts the cards of a single player from the public events and their own private events.
+/// The events have to be passed in the order they were emitted. Everything is forgotten once a
+/// round has a `PublicEvent::Result`, so a tracker can follow a whole game.
+#[derive(Debug, Clone)]
+pub struct KnowledgeTracker {
+    player_id: String,
+    hand: CardSet,
+    /// Every card which was in the own hand during the round.
+    dealt: CardSet,
+    played: CardSet,
+    trump: Option<CardSuit>,
+    trump_card: Option<Card>,
+    opponent: CardSet,
+    /// Cards the opponent showed they can't hold.
+    ruled_out: CardSet,
+    closed: bool,
+    exhausted: bool,
+}
+
+impl KnowledgeTracker {
+    pub fn new(player_id: impl Into<String>) -> Self {
+        Self {
+            player_id: player_id.into(),
+            hand: CardSet::EMPTY,
+            dealt: CardSet::EMPTY,
+            played: CardSet::EMPTY,
+            trump: None,
+            trump_card: None,
+            opponent: CardSet::EMPTY,
+            ruled_out: CardSet::EMPTY,
+            closed: false,
+            exhausted: false,
+        }
+    }
+
+    #[inline]
+    pub fn player_id(&self) -> &str {
+        &self.player_id
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(std::mem::take(&mut self.player_id));
+    }
+
+    pub fn on_priv_event(&mut self, event: &PrivateEvent) {
+        match event {
+            PrivateEvent::CardAvailabe(card) => {
+                self.hand.insert(*card);
+                self.dealt.insert(*card);
+                self.opponent.remove(*card);
+            }
+            PrivateEvent::CardUnavailabe(card) => {
+                self.hand.remove(*card);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn on_pub_event(&mut self, event: &PublicEvent) {
+        match event {
+            PublicEvent::PlayCard { user_id, card } => {
+                self.played.insert(*card);
+                if *user_id == self.player_id {
+                    self.hand.remove(*card);
+                }
+                self.opponent.remove(*card);
+            }
+            PublicEvent::Trick { cards, .. } => {
+                let [lead, response] = *cards;
+                self.played |= CardSet::from(cards.as_slice());
+                self.opponent -= CardSet::from(cards.as_slice());
+                if self.dealt.contains(lead) && !self.dealt.contains(response) {
+                    if let Some(trump) = self.trump {
+                        self.ruled_out |= ruled_out_by_response(
+                            lead,
+                            response,
+                            trump,
+                            self.closed,
+                            self.exhausted,
+                        );
+                    }
+                }
+            }
+            PublicEvent::Announce {
+                user_id,
+                announcement,
+            } if *user_id != self.player_id => {
+                self.opponent |= CardSet::from(announcement.cards.as_slice()) - self.played;
+            }
+            PublicEvent::TrumpChange(trump_card) => {
+                // The previous trump card went to whoever swapped or drew it
+                if let Some(previous) = self.trump_card.take() {
+                    if !self.hand.contains(previous) {
+                        self.opponent.insert(previous);
+                    }
+                }
+                match trump_card {
+                    Some(card) => {
+                        self.trump = Some(card.suit);
+                        self.trump_card = Some(*card);
+                        self.opponent.remove(*card);
+                    }
+                    None => self.exhausted = true,
+                }
+            }
+            PublicEvent::CloseTalon { .. } => self.closed = true,
+            PublicEvent::Result { .. } => self.reset(),
+            _ => {}
+        }
+    }
+
+    pub fn location(&self, card: Card) -> CardLocation {
+        if self.hand.contains(card) {
+            CardLocation::Hand
+        } else if self.played.contains(card) {
+            CardLocation::Played
+        } else if self.trump_card == Some(card) {
+            CardLocation::Trump
+        } else if self.exhausted || self.opponent.contains(card) {
+            CardLocation::Opponent
+        } else if self.ruled_out.contains(card) {
+            CardLocation::Talon
+        } else {
+            CardLocation::Unknown
+        }
+    }
+
+    #[inline]
+    pub fn hand(&self) -> CardSet {
+        self.hand
+    }
+
+    #[inline]
+    pub fn played(&self) -> CardSet {
+        self.played
+    }
+
+    #[inline]
+    pub fn trump(&self) -> Option<CardSuit> {
+        self.trump
+    }
+
+    #[inline]
+    pub fn trump_card(&self) -> Option<Card> {
+        self.trump_card
+    }
+
+    /// Whether no more cards can be drawn, because the talon is closed or empty.
+    #[inline]
+    pub fn is_talon_exhausted(&self) -> bool {
+        self.closed || self.exhausted
+    }
+
+    /// The cards which are either in the opponent's hand or in the talon.
+    pub fn unseen(&self) -> CardSet {
+        let mut unseen = CardSet::FULL - self.hand - self.played;
+        if let Some(card) = self.trump_card {
+            unseen.remove(card);
+        }
+        unseen
+    }
+
+    /// The cards which are certainly in the opponent's hand.
+    pub fn opponent_cards(&self) -> CardSet {
+        if self.exhausted {
+            self.unseen()
+        } else {
+            self.opponent & self.unseen()
+        }
+    }
+
+    /// The cards the opponent can't hold, which are in the talon unless it has been drawn.
+    pub fn ruled_out(&self) -> CardSet {
+        if self.exhausted {
+            CardSet::EMPTY
+        } else {
+            (self.ruled_out & self.unseen()) - self.opponent
+        }
+    }
+}
@@ -5,14 +5,23 @@ use std::ops::Index;
 use std::sync::{Arc, RwLock};
 
 use models::{contains_card_comb, has_announcable, Announcement};
-use models::{Card, Player};
+use agent::Observation;
+use knowledge::KnowledgeTracker;
+use models::{AnnounceType, Action, Card, CardSet, CardSuit, Player};
+use notation::GameRecord;
 use rand::prelude::*;
-use rand::thread_rng;
+use rand::rngs::StdRng;
 use serde::Deserialize;
 use serde::Serialize;
 
+pub mod agent;
 pub mod client;
+pub mod knowledge;
 pub mod models;
+pub mod notation;
+pub mod solver;
+pub mod state;
+pub mod stats;
 
 #[cfg(test)]
 mod tests;
@@ -34,6 +43,9 @@ pub enum PlayerError {
     CantTakeCardPlayerNotActive,
     CantTakeCardHaveAlreadyFive,
     TalonAlreadyClosed,
+    CardsNotDealt,
+    CardsAlreadyDealt,
+    NotInOpening,
 }
 
 impl PlayerError {
@@ -72,6 +84,15 @@ impl PlayerError {
             PlayerError::TalonAlreadyClosed => {
                 "Talon is already closed"
             }
+            PlayerError::CardsNotDealt => {
+                "The cards of the round have not been dealt"
+            }
+            PlayerError::CardsAlreadyDealt => {
+                "The cards of the round have already been dealt"
+            }
+            PlayerError::NotInOpening => {
+                "The round doesn't wait for this decision before the deal"
+            }
         }
     }
 }
@@ -111,9 +132,9 @@ pub enum PrivateEvent {
     AllowAnnounce,
     AllowCloseTalon,
     AllowSwapTrump,
+    AllowCuttDeck,
 }
 
-// TODO: Alle user_ids are currently serialized as the write-tokens. This has to be changed. SECURITY RISK
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "event", content = "data")]
 pub enum PublicEvent {
@@ -158,6 +179,36 @@ pub enum PublicEvent {
         user_id: String,
     },
     FinishedDistribution,
+    CuttDeck {
+        user_id: String,
+        cards: usize,
+    },
+}
+
+/// The winner of a finished round and the game points they received.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundOutcome {
+    pub winner: String,
+    pub points: u8,
+}
+
+/// What the active player decides before the cards of an opened round are dealt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Opening {
+    /// Cutting the deck, by any number of cards.
+    Cut,
+    /// Taking up the first packet of the deal, of three or two cards.
+    Draw,
+}
+
+impl Opening {
+    /// Every way to decide.
+    pub fn actions(&self) -> Vec<Action> {
+        match self {
+            Opening::Cut => (1..20).map(Action::CuttDeck).collect(),
+            Opening::Draw => vec![Action::TakeCards(3), Action::TakeCards(2)],
+        }
+    }
 }
 
 type FPub = Arc<dyn Fn(PublicEvent) -> () + Send + Sync + 'static>;
@@ -173,6 +224,16 @@ pub struct SchnapsenDuo {
     stack: Vec<Card>,
     pub_callbacks: Vec<FPub>,
     priv_callbacks: HashMap<String, Vec<FPriv>>,
+    record: GameRecord,
+    /// The announcement of the active player, one of whose cards has to be played next.
+    announced: Option<Announcement>,
+    /// The decision the round waits for before the deal, if it was opened.
+    opening: Option<Opening>,
+    outcome: Option<RoundOutcome>,
+    /// Shuffles the deck of every round.
+    rng: StdRng,
+    /// What each player knows about the cards, fed with the events they are sent.
+    knowledge: [RwLock<KnowledgeTracker>; 2],
 }
 
 unsafe impl Send for SchnapsenDuo {}
@@ -190,7 +251,16 @@ impl Hash for SchnapsenDuo {
 
 impl SchnapsenDuo {
     pub fn new(player_ids: &[String; 2]) -> Self {
-        let deck = Self::populate_deck();
+        Self::with_rng(player_ids, StdRng::from_entropy())
+    }
+
+    /// A game whose decks are shuffled the same way for the same `seed`.
+    pub fn seeded(player_ids: &[String; 2], seed: u64) -> Self {
+        Self::with_rng(player_ids, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(player_ids: &[String; 2], mut rng: StdRng) -> Self {
+        let deck = Self::populate_deck(&mut rng);
         let players = [
             Arc::new(RwLock::new(Player::new(player_ids[0].clone()))),
             Arc::new(RwLock::new(Player::new(player_ids[1].clone()))),
@@ -206,9 +276,34 @@ impl SchnapsenDuo {
             priv_callbacks: HashMap::new(),
             taken_trump: None,
             closed_talon: None,
+            record: GameRecord::new(player_ids.clone()),
+            announced: None,
+            opening: None,
+            outcome: None,
+            rng,
+            knowledge: player_ids
+                .clone()
+                .map(|player_id| RwLock::new(KnowledgeTracker::new(player_id))),
         }
     }
 
+    /// The result of the last round, until the next round starts.
+    #[inline]
+    pub fn round_outcome(&self) -> Option<&RoundOutcome> {
+        self.outcome.as_ref()
+    }
+
+    /// The deal and every action of the current round so far.
+    #[inline]
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    #[inline]
+    fn record_action(&mut self, player: &Player, action: Action) {
+        self.record.push(&player.id, action);
+    }
+
     #[inline]
     pub fn on_priv_event(
         &mut self,
@@ -245,6 +340,13 @@ impl SchnapsenDuo {
         }
     }
 
+    /// Forgets every listener, e.g. once the match is over. Listeners which hold on to whoever
+    /// owns the match would otherwise keep both alive.
+    pub fn clear_callbacks(&mut self) {
+        self.pub_callbacks.clear();
+        self.priv_callbacks.clear();
+    }
+
     pub fn get_player(&self, player_id: &str) -> Option<Arc<RwLock<Player>>> {
         self.players
             .iter()
@@ -261,7 +363,7 @@ impl SchnapsenDuo {
     }
 
     #[inline]
-    fn get_active_player(&self) -> Option<Arc<RwLock<Player>>> {
+    pub fn get_active_player(&self) -> Option<Arc<RwLock<Player>>> {
         self.active.clone()
     }
 
@@ -285,15 +387,164 @@ impl SchnapsenDuo {
         if cards_to_take > self.deck.len() {
             cards_to_take = self.deck.len();
         }
+        if self.opening == Some(Opening::Draw) {
+            return Err(PlayerError::NotInOpening);
+        }
         let (back, front) = self.deck.split_at(cards_to_take);
         self.deck = front.into_iter().chain(back.into_iter()).cloned().collect();
+        self.record_action(player, Action::CuttDeck(cards_to_take));
+        self.notify_pub(PublicEvent::CuttDeck {
+            user_id: player.id.clone(),
+            cards: cards_to_take,
+        });
+
+        if self.opening == Some(Opening::Cut) {
+            self.opening = Some(Opening::Draw);
+            self.notify_priv(player.id.clone(), PrivateEvent::AllowDrawCard);
+        }
         Ok(())
     }
 
+    /// Lets the active player cut the deck and take up their first packet before the cards are
+    /// dealt, instead of dealing right away. They are asked with `AllowCuttDeck`, then with
+    /// `AllowDrawCard`, and the cards are dealt once they took them with `take_cards_til`.
+    pub fn open_round(&mut self) -> Result<(), PlayerError> {
+        let Some(active) = self.active.clone() else {
+            return Err(PlayerError::NoPlayerActive);
+        };
+        if self.trump_suit().is_some() {
+            return Err(PlayerError::CardsAlreadyDealt);
+        }
+
+        self.opening = Some(Opening::Cut);
+        let player_id = active.read().unwrap().id.clone();
+        self.notify_priv(player_id, PrivateEvent::AllowCuttDeck);
+        Ok(())
+    }
+
+    /// The decision the round waits for before the deal, if it was opened.
+    #[inline]
+    pub fn opening(&self) -> Option<Opening> {
+        self.opening
+    }
+
     pub fn is_active(&self, player: &Player) -> bool {
         self.active.as_ref().unwrap().read().unwrap().id == player.id
     }
 
+    #[inline]
+    fn is_active_player(&self, player: &Arc<RwLock<Player>>) -> bool {
+        self.active
+            .as_ref()
+            .is_some_and(|active| Arc::ptr_eq(active, player))
+    }
+
+    /// The suit of the trump card, whether it is still below the talon or already taken.
+    #[inline]
+    pub fn trump_suit(&self) -> Option<CardSuit> {
+        self.trump
+            .as_ref()
+            .or(self.taken_trump.as_ref().map(|(_, card)| card))
+            .map(|card| card.suit)
+    }
+
+    #[inline]
+    fn knowledge_of(&self, player_id: &str) -> Option<&RwLock<KnowledgeTracker>> {
+        self.knowledge
+            .iter()
+            .find(|knowledge| knowledge.read().unwrap().player_id() == player_id)
+    }
+
+    /// The observation of the given player, or `None` if the cards have not been dealt yet.
+    pub fn observe(&self, player: Arc<RwLock<Player>>) -> Option<Observation> {
+        let trump = self.trump_suit()?;
+        let opponent = self.get_other_player(player.clone())?;
+        let lead = if self.stack.len() == 1 && self.is_active_player(&player) {
+            self.stack.first().copied()
+        } else {
+            None
+        };
+
+        let player = player.read().unwrap();
+        let opponent = opponent.read().unwrap();
+        let knowledge = self.knowledge_of(&player.id)?.read().unwrap();
+        let won = |player: &Player| player.tricks.iter().flatten().collect::<CardSet>();
+
+        Some(Observation {
+            player_id: player.id.clone(),
+            hand: player.card_set(),
+            playable: player.playable_cards.iter().collect(),
+            trump,
+            trump_card: self.trump,
+            deck_size: self.deck.len(),
+            closed_talon: self
+                .closed_talon
+                .as_ref()
+                .map(|closed| closed.read().unwrap().id.clone()),
+            lead,
+            won: won(&player),
+            opponent_won: won(&opponent),
+            points: player.trick_points(),
+            opponent_points: opponent.trick_points(),
+            announcements: player.announcements.clone(),
+            opponent_announcements: opponent.announcements.clone(),
+            opponent_hand_size: opponent.cards.len(),
+            opponent_revealed: knowledge.opponent_cards(),
+            opponent_ruled_out: knowledge.ruled_out(),
+        })
+    }
+
+    /// All actions the given player can take right now. Empty if it's not the players turn.
+    pub fn legal_actions(&self, player: Arc<RwLock<Player>>) -> Vec<Action> {
+        if !self.is_active_player(&player) {
+            return Vec::new();
+        }
+        if let Some(opening) = self.opening {
+            return opening.actions();
+        }
+        let player = player.read().unwrap();
+
+        let mut actions: Vec<_> = player
+            .playable_cards
+            .iter()
+            .copied()
+            .map(Action::PlayCard)
+            .collect();
+
+        // After announcing, one of the announced cards has to be played
+        if !self.stack.is_empty() || self.announced.is_some() {
+            return actions;
+        }
+
+        for announcement in &player.announcable {
+            // A marriage in trump always counts forty
+            let trump_twenty = announcement.announce_type == AnnounceType::Twenty
+                && self.trump_suit() == Some(announcement.suit());
+            if trump_twenty
+                || player
+                    .announcements
+                    .iter()
+                    .any(|announced| announced.suit() == announcement.suit())
+            {
+                continue;
+            }
+            actions.push(match announcement.announce_type {
+                AnnounceType::Twenty => Action::Announce20(announcement.cards),
+                AnnounceType::Forty => Action::Announce40,
+            });
+        }
+
+        if let Some(card) = self.can_swap_trump(&player) {
+            actions.push(Action::SwapTrump(*card));
+        }
+
+        if self.closed_talon.is_none() && !self.deck.is_empty() {
+            actions.push(Action::CloseTalon);
+        }
+
+        actions
+    }
+
     fn draw_card_after_trick(&mut self, player: Arc<RwLock<Player>>) -> Result<Card, PlayerError> {
         let card = {
             let player = &player.read().unwrap();
@@ -318,7 +569,7 @@ impl SchnapsenDuo {
             }
         };
 
-        player.write().unwrap().cards.push(card.clone());
+        player.write().unwrap().cards.push(card);
 
         let new = self.get_other_player(player).unwrap();
         let new_id = new.try_read().unwrap().id.clone();
@@ -346,7 +597,7 @@ impl SchnapsenDuo {
 
         self.notify_pub(PublicEvent::DeckCardCount(self.deck.len()));
 
-        self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(card.clone()));
+        self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(card));
         self.notify_pub(PublicEvent::ReceiveCard {
             user_id: player.id.clone(),
         });
@@ -369,6 +620,7 @@ impl SchnapsenDuo {
         }
 
         self.closed_talon = Some(self.get_owned_player(player));
+        self.record_action(player, Action::CloseTalon);
         self.notify_pub(PublicEvent::CloseTalon {
             user_id: player.id.clone(),
         });
@@ -382,6 +634,23 @@ impl SchnapsenDuo {
         player: Arc<RwLock<Player>>,
         idx: usize,
     ) -> Result<Vec<Card>, PlayerError> {
+        // In the opening, the player takes up the first packet of the deal
+        if self.opening.is_some() {
+            if self.opening != Some(Opening::Draw) {
+                return Err(PlayerError::NotInOpening);
+            }
+            if !self.is_active_player(&player) {
+                return Err(PlayerError::CantTakeCardPlayerNotActive);
+            }
+            if !(2..=3).contains(&idx) {
+                return Err(PlayerError::CantTakeAllDeckCards);
+            }
+            self.opening = None;
+            self.record_action(&player.read().unwrap(), Action::TakeCards(idx));
+            self.deal(idx)?;
+            return Ok(player.read().unwrap().cards.clone());
+        }
+
         let player = &player.read().unwrap();
         if idx >= self.deck.len() - 1 {
             return Err(PlayerError::CantTakeAllDeckCards);
@@ -393,13 +662,14 @@ impl SchnapsenDuo {
         let cards = self.deck.drain(..idx).collect::<Vec<_>>();
 
         for card in &cards {
-            self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(card.clone()));
+            self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(*card));
             self.notify_pub(PublicEvent::ReceiveCard {
                 user_id: player.id.clone(),
             });
         }
 
         self.notify_pub(PublicEvent::DeckCardCount(self.deck.len()));
+        self.record_action(player, Action::TakeCards(idx));
         Ok(cards)
     }
 
@@ -415,19 +685,30 @@ impl SchnapsenDuo {
         Ok(self
             .players
             .iter()
-            .max_by_key(|player| player.read().unwrap().cards.last().unwrap().value.clone() as u8)
+            .max_by_key(|player| player.read().unwrap().cards.last().unwrap().value as u8)
             .unwrap()
             .clone())
     }
 
     pub fn recreate_deck(&mut self) {
-        self.deck = Self::populate_deck().into();
+        self.deck = Self::populate_deck(&mut self.rng).into();
+        self.outcome = None;
+        self.record = GameRecord::new(self.record.players.clone());
         self.players.iter().for_each(|player| {
             player.write().unwrap().reset();
         });
+        self.knowledge
+            .iter()
+            .for_each(|knowledge| knowledge.write().unwrap().reset());
     }
 
     pub fn distribute_cards(&mut self) -> Result<(), PlayerError> {
+        self.deal(3)
+    }
+
+    /// Deals the first packet of `first` cards to each player, turns up the trump card and deals
+    /// the rest of the hands, the active player first.
+    fn deal(&mut self, first: usize) -> Result<(), PlayerError> {
         if self.active.is_none() {
             return Err(PlayerError::NoPlayerActive);
         }
@@ -439,20 +720,27 @@ impl SchnapsenDuo {
 
         let mut callbacks = Vec::new();
         for player in player_order.clone() {
-            for _ in 0..3 {
+            for _ in 0..first {
                 callbacks.extend(self.do_cards(&mut player.write().unwrap()));
             }
         }
         let trump = self.deck.pop().unwrap();
-        self.notify_pub(PublicEvent::TrumpChange(Some(trump.clone())));
+        self.notify_pub(PublicEvent::TrumpChange(Some(trump)));
         let _ = self.trump.insert(trump);
 
         for player in player_order.clone() {
-            for _ in 0..2 {
+            for _ in first..5 {
                 callbacks.extend(self.do_cards(&mut player.write().unwrap()));
             }
         }
 
+        self.record.hands = self
+            .players
+            .each_ref()
+            .map(|player| player.read().unwrap().cards.clone());
+        self.record.trump = Some(trump);
+        self.record.talon = self.deck.iter().rev().copied().collect();
+
         callbacks.extend(
             player_order
                 .into_iter()
@@ -519,17 +807,19 @@ impl SchnapsenDuo {
             .playable_cards
             .retain(|x| *x != card);
 
-        self.stack.push(card.clone());
+        self.stack.push(card);
+        self.announced = None;
+        self.record_action(&player.read().unwrap(), Action::PlayCard(card));
 
         let player_id = player.read().unwrap().id.clone();
         self.notify_priv(
             player_id.clone(),
-            PrivateEvent::CardUnavailabe(card.clone()),
+            PrivateEvent::CardUnavailabe(card),
         );
 
         self.notify_priv(
             player_id.clone(),
-            PrivateEvent::CardNotPlayable(card.clone()),
+            PrivateEvent::CardNotPlayable(card),
         );
 
         self.notify_pub(PublicEvent::PlayCard {
@@ -558,23 +848,22 @@ impl SchnapsenDuo {
         if self.active.is_none() || !self.is_active(player) {
             return Err(PlayerError::PlayerNotActive);
         }
-        if let Some(swap) = self.can_swap_trump(player) {
-            if *swap != card {
-                return Err(PlayerError::CantSwapTrump);
-            }
-            let trump = self.trump.take().unwrap();
+        match self.can_swap_trump(player) {
+            Some(swap) if *swap == card => {}
+            _ => return Err(PlayerError::CantSwapTrump),
+        }
+        let trump = self.trump.take().unwrap();
 
-            self.trump = Some(card.clone());
+        self.trump = Some(card);
+        self.record_action(player, Action::SwapTrump(card));
 
-            self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(trump.clone()));
-            self.notify_priv(player.id.clone(), PrivateEvent::CardUnavailabe(card));
+        self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(trump));
+        self.notify_priv(player.id.clone(), PrivateEvent::CardUnavailabe(card));
 
-            self.notify_pub(PublicEvent::TrumpChange(Some(
-                self.trump.as_ref().unwrap().clone(),
-            )));
-            return Ok(trump);
-        }
-        Err(PlayerError::CantSwapTrump)
+        self.notify_pub(PublicEvent::TrumpChange(Some(
+            *self.trump.as_ref().unwrap(),
+        )));
+        Ok(trump)
     }
 
     pub fn announce_40(&mut self, player: &Player) -> Result<Announcement, PlayerError> {
@@ -587,6 +876,8 @@ impl SchnapsenDuo {
             cards: cards_to_announce.unwrap(),
             announce_type: models::AnnounceType::Forty,
         };
+        self.record_action(player, Action::Announce40);
+        self.announced = Some(announcement.clone());
 
         self.notify_pub(PublicEvent::Announce {
             user_id: player.id.clone(),
@@ -602,7 +893,7 @@ impl SchnapsenDuo {
         cards: [Card; 2],
     ) -> Result<Announcement, PlayerError> {
         let announce = self.can_announce_20(player);
-        if announce.is_empty() || !contains_card_comb(&announce, cards.clone()) {
+        if announce.is_empty() || !contains_card_comb(&announce, cards) {
             return Err(PlayerError::CantPlay20);
         }
 
@@ -610,6 +901,8 @@ impl SchnapsenDuo {
             cards,
             announce_type: models::AnnounceType::Twenty,
         };
+        self.record_action(player, Action::Announce20(cards));
+        self.announced = Some(announcement.clone());
 
         self.notify_pub(PublicEvent::Announce {
             user_id: player.id.clone(),
@@ -634,17 +927,16 @@ impl SchnapsenDuo {
             return Vec::new();
         }
 
-        (0..4)
-            .filter_map(move |suit: u8| {
-                let mut cards_iter = player.cards.iter().filter(|card| {
-                    card.suit == suit.into()
-                        && (card.value == models::CardVal::Queen
-                            || card.value == models::CardVal::King)
-                });
-                if let (Some(card1), Some(card2)) = (cards_iter.next(), cards_iter.next()) {
-                    Some([card1.clone(), card2.clone()])
-                } else {
-                    None
+        let pairs = player.card_set()
+            & (CardSet::value(models::CardVal::Queen) | CardSet::value(models::CardVal::King));
+
+        models::CardSuit::ALL
+            .into_iter()
+            .filter_map(|suit| {
+                let mut cards = pairs.of_suit(suit).iter();
+                match (cards.next(), cards.next()) {
+                    (Some(queen), Some(king)) => Some([queen, king]),
+                    _ => None,
                 }
             })
             .collect()
@@ -656,21 +948,13 @@ impl SchnapsenDuo {
             return None;
         }
 
-        for pretender in cards_to_announce {
-            // TODO: As this is needed more often, move this into it's own function
-            let trump = match self.trump {
-                Some(ref trump) => trump,
-                None => &self.taken_trump.as_ref().unwrap().1,
-            };
-
-            if pretender.first().unwrap().suit == trump.suit {
-                return Some(pretender);
-            }
-        }
-        None
+        let trump = self.trump_suit()?;
+        cards_to_announce
+            .into_iter()
+            .find(|pretender| pretender.first().unwrap().suit == trump)
     }
 
-    fn populate_deck() -> [Card; 20] {
+    fn populate_deck(rng: &mut StdRng) -> [Card; 20] {
         let mut deck = (0..4)
             .map(|suit: u8| {
                 (10..12).chain(2..5).map(move |value: u8| Card {
@@ -681,14 +965,16 @@ impl SchnapsenDuo {
             .flatten()
             .collect::<Vec<Card>>();
 
-        let mut rng = thread_rng();
-        deck.shuffle(&mut rng);
+        deck.shuffle(rng);
         deck.try_into()
             .expect("Programming error. Populated deck is not of length 20")
     }
 
     // TODO: Change the return type to one simple JoinHandle
     fn notify_pub(&self, event: PublicEvent) -> Vec<std::thread::JoinHandle<()>> {
+        self.knowledge
+            .iter()
+            .for_each(|knowledge| knowledge.write().unwrap().on_pub_event(&event));
         Self::notify(self.pub_callbacks.clone(), event)
     }
 
@@ -709,6 +995,9 @@ impl SchnapsenDuo {
         user_id: String,
         event: PrivateEvent,
     ) -> Vec<std::thread::JoinHandle<()>> {
+        if let Some(knowledge) = self.knowledge_of(&user_id) {
+            knowledge.write().unwrap().on_priv_event(&event);
+        }
         let mut handles = Vec::new();
         if let Some(callbacks) = self.priv_callbacks.get(&user_id).cloned() {
             for callback in callbacks {
@@ -738,7 +1027,7 @@ impl SchnapsenDuo {
         let mut announcements = announcable_cards
             .iter()
             .map(|card| Announcement {
-                cards: card.clone(),
+                cards: *card,
                 announce_type: models::AnnounceType::Twenty,
             })
             .collect::<Vec<_>>();
@@ -762,7 +1051,7 @@ impl SchnapsenDuo {
             if !has_announcable(&announcements, announcement) {
                 announcable.retain(|x| {
                     x.announce_type != announcement.announce_type
-                        && x.cards.first().unwrap().suit != announcement.cards.first().unwrap().suit
+                        || x.suit() != announcement.suit()
                 });
 
                 callbacks.extend(self.notify_priv(
@@ -784,7 +1073,7 @@ impl SchnapsenDuo {
                 continue;
             }
 
-            if !player.has_announcable(&announcement) {
+            if !has_announcable(&announcable, &announcement) {
                 announcable.push(announcement.clone());
                 callbacks
                     .extend(self.notify_priv(id.clone(), PrivateEvent::CanAnnounce(announcement)));
@@ -813,18 +1102,18 @@ impl SchnapsenDuo {
         let can_swap = player.possible_trump_swap.is_some();
         let id = player.id.clone();
         if let Some(swap) = self.can_swap_trump(player) {
-            let card = swap.clone();
+            let card = *swap;
             if can_swap {
                 return (callbacks, Some(card));
             }
-            callbacks.extend(self.notify_priv(id, PrivateEvent::TrumpChangePossible(card.clone())));
+            callbacks.extend(self.notify_priv(id, PrivateEvent::TrumpChangePossible(card)));
             return (callbacks, Some(card));
         }
         if can_swap {
             callbacks.extend(self.notify_priv(
                 id,
                 PrivateEvent::TrumpChangeImpossible(
-                    player.possible_trump_swap.as_ref().unwrap().clone(),
+                    *player.possible_trump_swap.as_ref().unwrap(),
                 ),
             ));
         }
@@ -832,14 +1121,20 @@ impl SchnapsenDuo {
     }
 
     fn swap_to(&mut self, player: Arc<RwLock<Player>>) {
+        // Only the active player can announce, so the player has to be active before updating
+        let previous = self.active.replace(player.clone());
+
         self.update_announcable_props(player.clone());
         self.update_swap_trump(player.clone());
         self.update_playable_cards(player.clone());
-        if self.active.is_some() && Arc::ptr_eq(&player, self.active.as_ref().unwrap()) {
+        if previous
+            .as_ref()
+            .is_some_and(|previous| Arc::ptr_eq(previous, &player))
+        {
             return;
         }
 
-        if let Some(active) = &self.active {
+        if let Some(active) = &previous {
             let user_id = active.read().unwrap().id.clone();
             self.notify_pub(PublicEvent::Inactive {
                 user_id: user_id.clone(),
@@ -863,33 +1158,7 @@ impl SchnapsenDuo {
         let enemy = players.first().unwrap();
         let active = players.last().unwrap();
 
-        let enemy_suit = enemy.1.suit.clone();
-        let active_suit = active.1.suit.clone();
-        let enemy_is_trump = {
-            if self.trump.is_some() {
-                enemy_suit == self.trump.as_ref().unwrap().suit
-            } else {
-                enemy_suit == self.taken_trump.as_ref().unwrap().1.suit
-            }
-        };
-
-        let active_is_trump = {
-            if self.trump.is_some() {
-                active_suit == self.trump.as_ref().unwrap().suit
-            } else {
-                active_suit == self.taken_trump.as_ref().unwrap().1.suit
-            }
-        };
-
-        if !active_is_trump && enemy_is_trump {
-            enemy
-        } else if active_is_trump && !enemy_is_trump {
-            active
-        } else if self.stack.last().unwrap().suit != self.stack.first().unwrap().suit {
-            enemy
-        } else if self.stack.last().unwrap().value.clone() as u8
-            > self.stack.first().unwrap().value.clone() as u8
-        {
+        if active.1.beats(&enemy.1, self.trump_suit().unwrap()) {
             active
         } else {
             enemy
@@ -923,7 +1192,38 @@ impl SchnapsenDuo {
             }
         }
 
+        self.end_round(winner, loser);
+        Ok(true)
+    }
+
+    /// Ends the round in favour of the opponent of `player`, e.g. once they ran out of time. The
+    /// opponent receives the game points they would for the tricks `player` has taken so far.
+    pub fn concede_round(&mut self, player: Arc<RwLock<Player>>) -> Result<(), PlayerError> {
+        if self.active.is_none() {
+            return Err(PlayerError::NoPlayerActive);
+        }
+
+        let winner = self
+            .players
+            .iter()
+            .find(|other| !Arc::ptr_eq(other, &player))
+            .unwrap()
+            .clone();
+        let points = player.read().unwrap().trick_points();
+
+        self.end_round(
+            PlayerPoint {
+                player: winner,
+                points: 0,
+            },
+            PlayerPoint { player, points },
+        );
+        Ok(())
+    }
+
+    fn end_round(&mut self, winner: PlayerPoint, loser: PlayerPoint) {
         self.active = None;
+        self.announced = None;
 
         let points;
         if loser.points == 0 {
@@ -935,6 +1235,10 @@ impl SchnapsenDuo {
         }
 
         winner.player.write().unwrap().points += points;
+        self.outcome = Some(RoundOutcome {
+            winner: winner.player.read().unwrap().id.clone(),
+            points,
+        });
 
         let mut ranked = HashMap::new();
         {
@@ -956,7 +1260,7 @@ impl SchnapsenDuo {
             .find(|player| player.read().unwrap().points >= 7)
             .is_none()
         {
-            return Ok(true);
+            return;
         }
 
         let mut res = HashMap::new();
@@ -973,30 +1277,13 @@ impl SchnapsenDuo {
             ranked: res,
             winner: winner.player.read().unwrap().id.clone(),
         });
-        Ok(true)
     }
 
     pub fn calc_points(&self) -> Result<CardComparisonResult, PlayerError> {
         let points = self
             .players
             .iter()
-            .map(|player| {
-                let player = player.read().unwrap();
-                player.tricks.iter().flatten().fold(
-                    player
-                        .announcements
-                        .iter()
-                        .map(|a| {
-                            if !player.tricks.is_empty() {
-                                a.announce_type.clone() as u8
-                            } else {
-                                0 as u8
-                            }
-                        })
-                        .sum(),
-                    |acc, card| acc + card.value.clone() as u8,
-                )
-            })
+            .map(|player| player.read().unwrap().trick_points())
             .zip(self.players.iter());
 
         let (max_points, winner) = points.clone().max_by_key(|(points, _)| *points).unwrap();
@@ -1034,11 +1321,11 @@ impl SchnapsenDuo {
             .get_winner([
                 &(
                     self.get_non_active_player().unwrap(),
-                    self.stack.first().unwrap().clone(),
+                    *self.stack.first().unwrap(),
                 ),
                 &(
                     self.get_active_player().unwrap(),
-                    self.stack.last().unwrap().clone(),
+                    *self.stack.last().unwrap(),
                 ),
             ])
             .0
@@ -1076,7 +1363,7 @@ impl SchnapsenDuo {
     fn do_cards(&mut self, player: &mut Player) -> Vec<std::thread::JoinHandle<()>> {
         let card = self.deck.pop().unwrap();
         let mut callbacks =
-            self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(card.clone()));
+            self.notify_priv(player.id.clone(), PrivateEvent::CardAvailabe(card));
         callbacks.extend(self.notify_pub(PublicEvent::ReceiveCard {
             user_id: player.id.clone(),
         }));
@@ -1085,7 +1372,28 @@ impl SchnapsenDuo {
     }
 
     pub fn next_round(&mut self, winner: Arc<RwLock<Player>>) {
+        self.reset_round(winner.clone());
+
+        self.distribute_cards().unwrap();
+
+        self.active = None;
+        self.swap_to(winner.clone());
+    }
+
+    /// Starts the next round like `next_round`, but opened like with `open_round`.
+    pub fn open_next_round(&mut self, winner: Arc<RwLock<Player>>) {
+        self.reset_round(winner.clone());
+
+        self.active = None;
+        self.make_active(winner);
+        self.open_round().unwrap();
+    }
+
+    /// Takes back the cards of the last round and shuffles a new deck, the winner leads next.
+    fn reset_round(&mut self, winner: Arc<RwLock<Player>>) {
         self.active.replace(winner.clone());
+        self.trump = None;
+        self.opening = None;
         self.closed_talon = None;
         self.taken_trump = None;
         self.stack.clear();
@@ -1096,7 +1404,7 @@ impl SchnapsenDuo {
             for card in player_lock.cards.iter() {
                 self.notify_priv(
                     player_lock.id.clone(),
-                    PrivateEvent::CardUnavailabe(card.clone()),
+                    PrivateEvent::CardUnavailabe(*card),
                 )
                 .into_iter()
                 .for_each(|handle| handle.join().unwrap());
@@ -1108,11 +1416,6 @@ impl SchnapsenDuo {
         self.notify_pub(PublicEvent::DeckCardCount(9));
 
         self.recreate_deck();
-
-        self.distribute_cards().unwrap();
-
-        self.active = None;
-        self.swap_to(winner.clone());
     }
 
     fn can_swap_trump<'a>(&self, player: &'a Player) -> Option<&'a Card> {
@@ -1140,7 +1443,7 @@ impl SchnapsenDuo {
                 {
                     return None;
                 }
-                Some(self.notify_priv(player.id.clone(), PrivateEvent::CardPlayable(card.clone())))
+                Some(self.notify_priv(player.id.clone(), PrivateEvent::CardPlayable(*card)))
             })
             .into_iter()
             .collect();
@@ -1149,7 +1452,7 @@ impl SchnapsenDuo {
             if !playable.iter().any(|x| x.to_owned() == card.to_owned()) {
                 return Some(self.notify_priv(
                     player.id.clone(),
-                    PrivateEvent::CardNotPlayable(card.clone()),
+                    PrivateEvent::CardNotPlayable(*card),
                 ));
             }
             None
@@ -1173,71 +1476,43 @@ impl SchnapsenDuo {
     }
 
     fn find_playable_cards(&self, player: Arc<RwLock<Player>>) -> Vec<Card> {
-        let mut playable = {
-            if !self.stack.is_empty() && (self.taken_trump.is_some() || self.closed_talon.is_some())
-            {
-                let trump = match self.trump {
-                    Some(ref trump) => trump,
-                    None => &self.taken_trump.as_ref().unwrap().1,
-                };
+        let hand = player.read().unwrap().card_set();
+        let mut playable = hand;
 
-                // Force color
-                let forcing_color: Vec<_> = player
-                    .read()
-                    .unwrap()
-                    .cards
-                    .iter()
-                    .filter(|card| card.suit == self.stack.first().unwrap().suit.clone())
-                    .cloned()
-                    .collect();
+        if let Some(first) = self.stack.first() {
+            let trump = self.trump_suit().unwrap();
 
-                if forcing_color.is_empty() {
-                    player
-                        .read()
-                        .unwrap()
-                        .cards
-                        .iter()
-                        .filter(|card| card.suit == trump.suit)
-                        .cloned()
-                        .collect()
+            if self.taken_trump.is_some() || self.closed_talon.is_some() {
+                // Force color
+                let forcing_color = hand.of_suit(first.suit);
+                playable = if forcing_color.is_empty() {
+                    hand.of_suit(trump)
                 } else {
                     forcing_color
-                }
-            } else {
-                player.read().unwrap().cards.clone()
+                };
             }
-        };
 
-        if playable.is_empty() {
-            playable = player.read().unwrap().cards.clone();
-        }
+            if playable.is_empty() {
+                playable = hand;
+            }
 
-        if self.closed_talon.is_some() && !self.stack.is_empty() {
-            // Force trick
-            let gonna_win: Vec<_> = playable
-                .clone()
-                .into_iter()
-                .filter(|card| {
-                    self.get_winner([
-                        &(
-                            self.get_non_active_player().unwrap().clone(),
-                            self.stack.first().unwrap().clone(),
-                        ),
-                        &(self.get_active_player().unwrap(), card.clone()),
-                    ])
-                    .0
-                    .read()
-                    .unwrap()
-                    .id == player.read().unwrap().id
-                })
-                .collect();
+            if self.closed_talon.is_some() {
+                // Force trick
+                let gonna_win: CardSet = playable
+                    .iter()
+                    .filter(|card| card.beats(first, trump))
+                    .collect();
 
-            if !gonna_win.is_empty() {
-                playable = gonna_win;
+                if !gonna_win.is_empty() {
+                    playable = gonna_win;
+                }
             }
+        } else if let Some(announced) = &self.announced {
+            // After announcing, one of the announced cards has to be played
+            playable = hand & CardSet::from(announced.cards.as_slice());
         }
 
-        playable
+        playable.to_vec()
     }
 
     fn take_trump(&mut self, player: &Player) -> Card {
@@ -1251,12 +1526,12 @@ impl SchnapsenDuo {
         self.notify_pub(PublicEvent::DeckCardCount(self.deck.len()));
         self.notify_priv(
             player.id.clone(),
-            PrivateEvent::CardAvailabe(taken_trump.1.clone()),
+            PrivateEvent::CardAvailabe(taken_trump.1),
         );
         self.notify_pub(PublicEvent::ReceiveCard {
             user_id: player.id.clone(),
         });
 
-        return taken_trump.1.clone();
+        return taken_trump.1;
     }
 }
@@ -0,0 +1,249 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Card, CardSuit, CardVal};
+
+const FULL_MASK: u32 = (1 << 20) - 1;
+const SUIT_MASK: u32 = 0b11111;
+
+/// A set of cards stored as a bitset, where bit `n` represents the card with `Card::index() == n`.
+/// Serializes to and from the same list format as `Vec<Card>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<Card>", into = "Vec<Card>")]
+pub struct CardSet(u32);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    pub const FULL: CardSet = CardSet(FULL_MASK);
+
+    #[inline]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits & FULL_MASK)
+    }
+
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn single(card: Card) -> Self {
+        Self(1 << card.index())
+    }
+
+    /// All five cards of the given suit.
+    #[inline]
+    pub const fn suit(suit: CardSuit) -> Self {
+        Self(SUIT_MASK << (suit as u8 * 5))
+    }
+
+    /// All four cards of the given value.
+    #[inline]
+    pub const fn value(value: CardVal) -> Self {
+        Self(0b00001_00001_00001_00001 << value.rank())
+    }
+
+    #[inline]
+    pub const fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub const fn contains(self, card: Card) -> bool {
+        self.0 & (1 << card.index()) != 0
+    }
+
+    /// Returns `true` if the card was not yet part of the set.
+    #[inline]
+    pub fn insert(&mut self, card: Card) -> bool {
+        let absent = !self.contains(card);
+        self.0 |= 1 << card.index();
+        absent
+    }
+
+    /// Returns `true` if the card was part of the set.
+    #[inline]
+    pub fn remove(&mut self, card: Card) -> bool {
+        let present = self.contains(card);
+        self.0 &= !(1 << card.index());
+        present
+    }
+
+    #[inline]
+    pub const fn union(self, other: CardSet) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline]
+    pub const fn intersection(self, other: CardSet) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    #[inline]
+    pub const fn difference(self, other: CardSet) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    #[inline]
+    pub const fn complement(self) -> Self {
+        Self(!self.0 & FULL_MASK)
+    }
+
+    #[inline]
+    pub const fn is_subset(self, other: CardSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// The cards of this set which belong to the given suit.
+    #[inline]
+    pub const fn of_suit(self, suit: CardSuit) -> Self {
+        self.intersection(Self::suit(suit))
+    }
+
+    /// The card with the lowest index, which is the weakest card of the lowest suit.
+    #[inline]
+    pub fn first(self) -> Option<Card> {
+        self.iter().next()
+    }
+
+    #[inline]
+    pub fn iter(self) -> CardSetIter {
+        CardSetIter(self.0)
+    }
+
+    #[inline]
+    pub fn to_vec(self) -> Vec<Card> {
+        self.iter().collect()
+    }
+}
+
+/// Iterates the cards of a `CardSet` in ascending index order.
+#[derive(Debug, Clone)]
+pub struct CardSetIter(u32);
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Card::from_index(index)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut set = CardSet::EMPTY;
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = &'a Card>>(iter: T) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<T: IntoIterator<Item = Card>>(&mut self, iter: T) {
+        for card in iter {
+            self.insert(card);
+        }
+    }
+}
+
+impl From<&[Card]> for CardSet {
+    fn from(cards: &[Card]) -> Self {
+        cards.iter().collect()
+    }
+}
+
+impl From<Vec<Card>> for CardSet {
+    fn from(cards: Vec<Card>) -> Self {
+        cards.into_iter().collect()
+    }
+}
+
+impl From<CardSet> for Vec<Card> {
+    fn from(set: CardSet) -> Self {
+        set.to_vec()
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for CardSet {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs);
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl BitAndAssign for CardSet {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = self.intersection(rhs);
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+impl SubAssign for CardSet {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.difference(rhs);
+    }
+}
+
+impl Not for CardSet {
+    type Output = CardSet;
+
+    fn not(self) -> Self::Output {
+        self.complement()
+    }
+}
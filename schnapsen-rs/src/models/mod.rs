@@ -1,47 +1,165 @@
+use std::cmp::Ordering;
 use std::hash::Hash;
 
 use num_enum::FromPrimitive;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+mod card_set;
+pub mod naming;
+
+pub use card_set::{CardSet, CardSetIter};
+pub use naming::{Named, Naming};
+
+/// Cards are ordered by suit first and rank second, which matches the order of `Card::index`.
+/// Use `Card::cmp_in_trick` to compare the strength of cards within a trick.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Card {
     pub value: CardVal,
     pub suit: CardSuit,
 }
 
+impl Card {
+    #[inline]
+    pub const fn new(value: CardVal, suit: CardSuit) -> Self {
+        Self { value, suit }
+    }
+
+    /// Compact encoding of the card in `0..20`: five consecutive ranks per suit, weakest first.
+    #[inline]
+    pub const fn index(&self) -> u8 {
+        self.suit as u8 * 5 + self.value.rank()
+    }
+
+    #[inline]
+    pub const fn from_index(index: u8) -> Option<Self> {
+        if index >= 20 {
+            return None;
+        }
+        Some(Self {
+            value: CardVal::ALL[(index % 5) as usize],
+            suit: CardSuit::ALL[(index / 5) as usize],
+        })
+    }
+
+    #[inline]
+    pub const fn points(&self) -> u8 {
+        self.value as u8
+    }
+
+    /// Compares by suit first and by rank within the suit. Same as `Ord`.
+    #[inline]
+    pub fn cmp_by_suit(&self, other: &Self) -> Ordering {
+        self.suit
+            .cmp(&other.suit)
+            .then(self.value.cmp(&other.value))
+    }
+
+    /// Compares by rank first and by suit between cards of the same rank.
+    #[inline]
+    pub fn cmp_by_rank(&self, other: &Self) -> Ordering {
+        self.value
+            .cmp(&other.value)
+            .then(self.suit.cmp(&other.suit))
+    }
+
+    /// Compares the strength of two cards in a trick with the given trump and led suit.
+    /// Trumps beat cards of the led suit, which beat all other cards. Cards which can't win the
+    /// trick are ordered by rank and then by suit, so that the ordering stays total.
+    #[inline]
+    pub fn cmp_in_trick(&self, other: &Self, trump: CardSuit, led: CardSuit) -> Ordering {
+        let strength = |card: &Card| (card.suit == trump, card.suit == led, card.value);
+        strength(self)
+            .cmp(&strength(other))
+            .then(self.suit.cmp(&other.suit))
+    }
+
+    /// Whether this card, played in response to `led`, takes the trick.
+    #[inline]
+    pub fn beats(&self, led: &Card, trump: CardSuit) -> bool {
+        self.cmp_in_trick(led, trump, led.suit) == Ordering::Greater
+    }
+}
+
 impl PartialOrd for Card {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.value.cmp(&other.value))
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Card {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.value.cmp(&other.value)
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_by_suit(other)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, FromPrimitive, PartialEq, Eq, PartialOrd, Ord)]
+/// Serializes with the French names, wrap it in a `Named` for another `Naming`.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, FromPrimitive, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 #[repr(u8)]
 pub enum CardVal {
+    #[serde(alias = "Zehner")]
     Ten = 10,
+    #[serde(alias = "Unter")]
     Jack = 2,
+    #[serde(alias = "Ober")]
     Queen = 3,
+    #[serde(alias = "König")]
     King = 4,
     #[default]
+    #[serde(alias = "Daus")]
     Ace = 11,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, FromPrimitive, PartialEq, Eq)]
+impl CardVal {
+    /// All values ordered by their rank, weakest first.
+    pub const ALL: [CardVal; 5] = [
+        CardVal::Jack,
+        CardVal::Queen,
+        CardVal::King,
+        CardVal::Ten,
+        CardVal::Ace,
+    ];
+
+    /// Position of the value in `CardVal::ALL`.
+    #[inline]
+    pub const fn rank(&self) -> u8 {
+        match self {
+            CardVal::Jack => 0,
+            CardVal::Queen => 1,
+            CardVal::King => 2,
+            CardVal::Ten => 3,
+            CardVal::Ace => 4,
+        }
+    }
+}
+
+/// Serializes with the French names, wrap it in a `Named` for another `Naming`.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, FromPrimitive, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 #[repr(u8)]
 pub enum CardSuit {
     #[default]
+    #[serde(alias = "Herz")]
     Hearts = 0,
+    #[serde(alias = "Schell", alias = "Bells")]
     Diamonds = 1,
+    #[serde(alias = "Eichel", alias = "Acorns")]
     Clubs = 2,
+    #[serde(alias = "Laub", alias = "Leaves")]
     Spades = 3,
 }
 
+impl CardSuit {
+    pub const ALL: [CardSuit; 4] = [
+        CardSuit::Hearts,
+        CardSuit::Diamonds,
+        CardSuit::Clubs,
+        CardSuit::Spades,
+    ];
+}
+
 #[derive(Debug, Clone)]
 pub struct Player {
     pub id: String,
@@ -73,13 +191,31 @@ impl Player {
         }
     }
 
+    #[inline]
+    pub fn card_set(&self) -> CardSet {
+        self.cards.iter().collect()
+    }
+
+    /// Points of the won tricks. Announcements only count once the player has won a trick.
+    pub fn trick_points(&self) -> u8 {
+        let announced: u8 = if self.tricks.is_empty() {
+            0
+        } else {
+            self.announcements
+                .iter()
+                .map(|announcement| announcement.announce_type as u8)
+                .sum()
+        };
+        self.tricks.iter().flatten().map(Card::points).sum::<u8>() + announced
+    }
+
     pub fn has_announced(&self, mut cards: [Card; 2]) -> bool {
         cards.sort();
         let announced = self
             .announcements
             .iter()
             .map(|x| {
-                let mut a = x.cards.clone();
+                let mut a = x.cards;
                 a.sort();
                 a
             })
@@ -104,7 +240,7 @@ pub fn contains_card_comb(data: &[[Card; 2]], mut check: [Card; 2]) -> bool {
     let announced = data
         .iter()
         .map(|x| {
-            let mut clone = x.clone();
+            let mut clone = *x;
             clone.sort();
             clone
         })
@@ -119,13 +255,13 @@ impl Hash for Player {
 }
 
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct Announcement {
     pub cards: [Card; 2],
     pub announce_type: AnnounceType,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, FromPrimitive, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, FromPrimitive, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum AnnounceType {
     Forty = 40,
@@ -133,3 +269,33 @@ pub enum AnnounceType {
     Twenty = 20,
 }
 
+impl Announcement {
+    /// Creates the announcement of the queen and king of the given suit.
+    pub const fn new(announce_type: AnnounceType, suit: CardSuit) -> Self {
+        Self {
+            cards: [
+                Card::new(CardVal::Queen, suit),
+                Card::new(CardVal::King, suit),
+            ],
+            announce_type,
+        }
+    }
+
+    #[inline]
+    pub const fn suit(&self) -> CardSuit {
+        self.cards[0].suit
+    }
+}
+
+/// A decision a player can take when it's their turn.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(tag = "action", content = "data")]
+pub enum Action {
+    CuttDeck(usize),
+    TakeCards(usize),
+    PlayCard(Card),
+    SwapTrump(Card),
+    CloseTalon,
+    Announce20([Card; 2]),
+    Announce40,
+}
@@ -0,0 +1,476 @@
+use core::fmt;
+
+use serde::ser::{
+    SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Card, CardSuit, CardVal};
+
+/// The names used for suits and values. Serializing uses `Naming::French` unless the value is
+/// wrapped in `Named`, while deserializing accepts the names of every naming.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Naming {
+    /// Hearts, Diamonds, Clubs and Spades with Jack, Queen, King, Ten and Ace.
+    #[default]
+    French,
+    /// The double-deck names Herz, Schell, Eichel and Laub with Unter, Ober, König, Zehner and Daus.
+    German,
+    /// The English names of the double-deck suits Hearts, Bells, Acorns and Leaves.
+    GermanEnglish,
+}
+
+impl Naming {
+    pub const ALL: [Naming; 3] = [Naming::French, Naming::German, Naming::GermanEnglish];
+
+    /// Picks the naming players of the given locale (e.g. `de-AT`) expect.
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        if language.eq_ignore_ascii_case("de") {
+            Naming::German
+        } else {
+            Naming::French
+        }
+    }
+}
+
+impl CardSuit {
+    pub const fn name(&self, naming: Naming) -> &'static str {
+        match (naming, self) {
+            (Naming::French, CardSuit::Hearts) => "Hearts",
+            (Naming::French, CardSuit::Diamonds) => "Diamonds",
+            (Naming::French, CardSuit::Clubs) => "Clubs",
+            (Naming::French, CardSuit::Spades) => "Spades",
+            (Naming::German, CardSuit::Hearts) => "Herz",
+            (Naming::German, CardSuit::Diamonds) => "Schell",
+            (Naming::German, CardSuit::Clubs) => "Eichel",
+            (Naming::German, CardSuit::Spades) => "Laub",
+            (Naming::GermanEnglish, CardSuit::Hearts) => "Hearts",
+            (Naming::GermanEnglish, CardSuit::Diamonds) => "Bells",
+            (Naming::GermanEnglish, CardSuit::Clubs) => "Acorns",
+            (Naming::GermanEnglish, CardSuit::Spades) => "Leaves",
+        }
+    }
+
+    #[inline]
+    pub fn named(&self, naming: Naming) -> Named<'_, CardSuit> {
+        Named::new(self, naming)
+    }
+
+    /// Looks the suit up by its name in any naming, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Naming::ALL.into_iter().find_map(|naming| {
+            CardSuit::ALL
+                .into_iter()
+                .find(|suit| suit.name(naming).to_lowercase() == name.to_lowercase())
+        })
+    }
+}
+
+impl CardVal {
+    pub const fn name(&self, naming: Naming) -> &'static str {
+        match (naming, self) {
+            (Naming::French, CardVal::Jack) => "Jack",
+            (Naming::French, CardVal::Queen) => "Queen",
+            (Naming::French, CardVal::King) => "King",
+            (Naming::French, CardVal::Ten) => "Ten",
+            (Naming::French, CardVal::Ace) => "Ace",
+            (Naming::German, CardVal::Jack) => "Unter",
+            (Naming::German, CardVal::Queen) => "Ober",
+            (Naming::German, CardVal::King) => "König",
+            (Naming::German, CardVal::Ten) => "Zehner",
+            (Naming::German, CardVal::Ace) => "Daus",
+            (Naming::GermanEnglish, CardVal::Jack) => "Unter",
+            (Naming::GermanEnglish, CardVal::Queen) => "Ober",
+            (Naming::GermanEnglish, CardVal::King) => "King",
+            (Naming::GermanEnglish, CardVal::Ten) => "Ten",
+            (Naming::GermanEnglish, CardVal::Ace) => "Ace",
+        }
+    }
+
+    #[inline]
+    pub fn named(&self, naming: Naming) -> Named<'_, CardVal> {
+        Named::new(self, naming)
+    }
+
+    /// Looks the value up by its name in any naming, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Naming::ALL.into_iter().find_map(|naming| {
+            CardVal::ALL
+                .into_iter()
+                .find(|value| value.name(naming).to_lowercase() == name.to_lowercase())
+        })
+    }
+}
+
+impl Card {
+    /// Displays the card with its full name, e.g. `Herz Daus` or `Ace of Hearts`.
+    #[inline]
+    pub fn named(&self, naming: Naming) -> Named<'_, Card> {
+        Named::new(self, naming)
+    }
+}
+
+/// Displays the wrapped card, suit or value with the names of a `Naming`. Serializes any wrapped
+/// value, e.g. a `Vec<Card>` or a `PublicEvent`, with the names of the naming.
+#[derive(Debug)]
+pub struct Named<'a, T: ?Sized> {
+    pub inner: &'a T,
+    pub naming: Naming,
+}
+
+impl<T: ?Sized> Clone for Named<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Named<'_, T> {}
+
+impl<'a, T: ?Sized> Named<'a, T> {
+    #[inline]
+    pub const fn new(inner: &'a T, naming: Naming) -> Self {
+        Self { inner, naming }
+    }
+}
+
+impl fmt::Display for Named<'_, Card> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suit = self.inner.suit.name(self.naming);
+        let value = self.inner.value.name(self.naming);
+        match self.naming {
+            Naming::German => write!(f, "{suit} {value}"),
+            Naming::French | Naming::GermanEnglish => write!(f, "{value} of {suit}"),
+        }
+    }
+}
+
+impl fmt::Display for Named<'_, CardSuit> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner.name(self.naming))
+    }
+}
+
+impl fmt::Display for Named<'_, CardVal> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner.name(self.naming))
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for Named<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.naming {
+            Naming::French => self.inner.serialize(serializer),
+            naming => self.inner.serialize(NamingSerializer {
+                inner: serializer,
+                naming,
+            }),
+        }
+    }
+}
+
+/// Passes everything on to the wrapped serializer, but writes the suits and values, which
+/// serialize as unit variants of `CardSuit` and `CardVal`, with the names of the naming.
+struct NamingSerializer<S> {
+    inner: S,
+    naming: Naming,
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            #[inline]
+            fn $method(self, $($arg: $ty),*) -> Result<S::Ok, S::Error> {
+                self.inner.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for NamingSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = NamingSerializer<S::SerializeSeq>;
+    type SerializeTuple = NamingSerializer<S::SerializeTuple>;
+    type SerializeTupleStruct = NamingSerializer<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = NamingSerializer<S::SerializeTupleVariant>;
+    type SerializeMap = NamingSerializer<S::SerializeMap>;
+    type SerializeStruct = NamingSerializer<S::SerializeStruct>;
+    type SerializeStructVariant = NamingSerializer<S::SerializeStructVariant>;
+
+    forward! {
+        serialize_bool(v: bool);
+        serialize_i8(v: i8);
+        serialize_i16(v: i16);
+        serialize_i32(v: i32);
+        serialize_i64(v: i64);
+        serialize_i128(v: i128);
+        serialize_u8(v: u8);
+        serialize_u16(v: u16);
+        serialize_u32(v: u32);
+        serialize_u64(v: u64);
+        serialize_u128(v: u128);
+        serialize_f32(v: f32);
+        serialize_f64(v: f64);
+        serialize_char(v: char);
+        serialize_str(v: &str);
+        serialize_bytes(v: &[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(name: &'static str);
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        let named = match name {
+            "CardSuit" => CardSuit::from_name(variant).map(|suit| suit.name(self.naming)),
+            "CardVal" => CardVal::from_name(variant).map(|value| value.name(self.naming)),
+            _ => None,
+        };
+        match named {
+            Some(named) => self.inner.serialize_str(named),
+            None => self
+                .inner
+                .serialize_unit_variant(name, variant_index, variant),
+        }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_some(&Named::new(value, self.naming))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_newtype_struct(name, &Named::new(value, self.naming))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            &Named::new(value, self.naming),
+        )
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let naming = self.naming;
+        let seq = self.inner.serialize_seq(len)?;
+        Ok(NamingSerializer { inner: seq, naming })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let naming = self.naming;
+        let tuple = self.inner.serialize_tuple(len)?;
+        Ok(NamingSerializer {
+            inner: tuple,
+            naming,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let naming = self.naming;
+        let tuple = self.inner.serialize_tuple_struct(name, len)?;
+        Ok(NamingSerializer {
+            inner: tuple,
+            naming,
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let naming = self.naming;
+        let tuple = self
+            .inner
+            .serialize_tuple_variant(name, variant_index, variant, len)?;
+        Ok(NamingSerializer {
+            inner: tuple,
+            naming,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let naming = self.naming;
+        let map = self.inner.serialize_map(len)?;
+        Ok(NamingSerializer { inner: map, naming })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let naming = self.naming;
+        let fields = self.inner.serialize_struct(name, len)?;
+        Ok(NamingSerializer {
+            inner: fields,
+            naming,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let naming = self.naming;
+        let fields = self
+            .inner
+            .serialize_struct_variant(name, variant_index, variant, len)?;
+        Ok(NamingSerializer {
+            inner: fields,
+            naming,
+        })
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Every element of a compound value is named like the value itself.
+macro_rules! compound {
+    ($($trait:ident::$method:ident),*) => {
+        $(
+            impl<C: $trait> $trait for NamingSerializer<C> {
+                type Ok = C::Ok;
+                type Error = C::Error;
+
+                #[inline]
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+                    self.inner.$method(&Named::new(value, self.naming))
+                }
+
+                #[inline]
+                fn end(self) -> Result<C::Ok, C::Error> {
+                    self.inner.end()
+                }
+            }
+        )*
+    };
+}
+
+compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl<C: SerializeMap> SerializeMap for NamingSerializer<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    #[inline]
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        self.inner.serialize_key(&Named::new(key, self.naming))
+    }
+
+    #[inline]
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_value(&Named::new(value, self.naming))
+    }
+
+    #[inline]
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+/// Every field of a struct is named like the struct itself.
+macro_rules! fields {
+    ($($trait:ident),*) => {
+        $(
+            impl<C: $trait> $trait for NamingSerializer<C> {
+                type Ok = C::Ok;
+                type Error = C::Error;
+
+                #[inline]
+                fn serialize_field<T: Serialize + ?Sized>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), C::Error> {
+                    self.inner.serialize_field(key, &Named::new(value, self.naming))
+                }
+
+                #[inline]
+                fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+                    self.inner.skip_field(key)
+                }
+
+                #[inline]
+                fn end(self) -> Result<C::Ok, C::Error> {
+                    self.inner.end()
+                }
+            }
+        )*
+    };
+}
+
+fields!(SerializeStruct, SerializeStructVariant);
+
+/// Use with `#[serde(with = "schnapsen_rs::models::naming::german")]` to emit a field, e.g. a
+/// `Card`, `Vec<Card>` or `Announcement`, with the German double-deck names.
+pub mod german {
+    use super::*;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Named::new(value, Naming::German).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Use with `#[serde(with = "schnapsen_rs::models::naming::german_english")]` to emit a field with
+/// the English names of the double-deck suits.
+pub mod german_english {
+    use super::*;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Named::new(value, Naming::GermanEnglish).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
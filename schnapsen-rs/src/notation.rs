@@ -0,0 +1,527 @@
+//! Compact text notation for cards, announcements, actions and complete games.
+//!
+//! Cards are written as suit followed by value (`HA`, `♠K`, `D10`), or spelled out with the names
+//! of any `Naming` (`Hearts Ace`, `Ace of Hearts`, `Herz Daus`, `Herz Ass`). Announcements are written as `20 H` or
+//! `40 S`, actions as `play HA`, `swap CJ`, `close`, `20 H`, `40`, `cut 3` and `take 4`.
+//!
+//! A game is written one statement per line:
+//!
+//! ```text
+//! # Comments and empty lines are ignored
+//! players alice bob
+//! alice cut 3
+//! bob take 4
+//! deal alice HA HK HQ SJ ST
+//! deal bob DA DK CQ CJ DT
+//! trump CA
+//! talon CT HT CK DQ SK SQ HJ SA DJ
+//! alice play HA
+//! bob play HK
+//! alice 20 S
+//! bob close
+//! ```
+//!
+//! `players`, `deal`, `trump` and `talon` are keywords, every other line starts with the name of
+//! the player who took the action. Names which are keywords or contain whitespace, `#`, `"` or `\`
+//! are written in double quotes, with `\"`, `\\`, `\n` and `\r` escaped, as in
+//! `players "players" "Anna Maria"`. `SchnapsenDuo::record` returns the record of the round being
+//! played.
+
+use core::fmt;
+use std::str::FromStr;
+
+use crate::models::{Action, AnnounceType, Announcement, Card, CardSuit, CardVal};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    InvalidSuit(String),
+    InvalidValue(String),
+    InvalidCard(String),
+    InvalidAnnouncement(String),
+    InvalidAction(String),
+    InvalidStatement(String),
+    MissingPlayers,
+    UnknownPlayer(String),
+    Line {
+        line: usize,
+        error: Box<NotationError>,
+    },
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::InvalidSuit(suit) => write!(f, "Invalid suit: {suit:?}"),
+            NotationError::InvalidValue(value) => write!(f, "Invalid card value: {value:?}"),
+            NotationError::InvalidCard(card) => write!(f, "Invalid card: {card:?}"),
+            NotationError::InvalidAnnouncement(announcement) => {
+                write!(f, "Invalid announcement: {announcement:?}")
+            }
+            NotationError::InvalidAction(action) => write!(f, "Invalid action: {action:?}"),
+            NotationError::InvalidStatement(statement) => {
+                write!(f, "Invalid statement: {statement:?}")
+            }
+            NotationError::MissingPlayers => {
+                write!(f, "The players have to be declared before they are used")
+            }
+            NotationError::UnknownPlayer(player) => write!(f, "Unknown player: {player:?}"),
+            NotationError::Line { line, error } => write!(f, "Line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+impl CardSuit {
+    #[inline]
+    pub const fn letter(&self) -> char {
+        match self {
+            CardSuit::Hearts => 'H',
+            CardSuit::Diamonds => 'D',
+            CardSuit::Clubs => 'C',
+            CardSuit::Spades => 'S',
+        }
+    }
+
+    #[inline]
+    pub const fn symbol(&self) -> char {
+        match self {
+            CardSuit::Hearts => '♥',
+            CardSuit::Diamonds => '♦',
+            CardSuit::Clubs => '♣',
+            CardSuit::Spades => '♠',
+        }
+    }
+}
+
+impl CardVal {
+    #[inline]
+    pub const fn letter(&self) -> char {
+        match self {
+            CardVal::Jack => 'J',
+            CardVal::Queen => 'Q',
+            CardVal::King => 'K',
+            CardVal::Ten => 'T',
+            CardVal::Ace => 'A',
+        }
+    }
+}
+
+/// Formats as the suit letter, or the suit symbol with `{:#}`.
+impl fmt::Display for CardSuit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{}", self.symbol())
+        } else {
+            write!(f, "{}", self.letter())
+        }
+    }
+}
+
+impl fmt::Display for CardVal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.letter())
+    }
+}
+
+/// Formats as `HA`, or as `♥A` with `{:#}`.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#}{}", self.suit, self.value)
+        } else {
+            write!(f, "{}{}", self.suit, self.value)
+        }
+    }
+}
+
+impl fmt::Display for AnnounceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", *self as u8)
+    }
+}
+
+/// Formats as `20 H`, or as `20 ♥` with `{:#}`.
+impl fmt::Display for Announcement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{} {:#}", self.announce_type, self.suit())
+        } else {
+            write!(f, "{} {}", self.announce_type, self.suit())
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::CuttDeck(cards) => write!(f, "cut {cards}"),
+            Action::TakeCards(cards) => write!(f, "take {cards}"),
+            Action::PlayCard(card) => write!(f, "play {card}"),
+            Action::SwapTrump(card) => write!(f, "swap {card}"),
+            Action::CloseTalon => write!(f, "close"),
+            Action::Announce20(cards) => write!(f, "20 {}", cards[0].suit),
+            Action::Announce40 => write!(f, "40"),
+        }
+    }
+}
+
+fn parse_suit(token: &str) -> Option<CardSuit> {
+    let suit = match token.to_lowercase().as_str() {
+        "h" | "♥" | "♡" | "heart" => Some(CardSuit::Hearts),
+        "d" | "♦" | "♢" | "diamond" | "karo" | "schellen" => Some(CardSuit::Diamonds),
+        "c" | "♣" | "♧" | "club" | "kreuz" => Some(CardSuit::Clubs),
+        "s" | "♠" | "♤" | "spade" | "pik" | "grün" => Some(CardSuit::Spades),
+        _ => None,
+    };
+    suit.or_else(|| CardSuit::from_name(token))
+}
+
+fn parse_value(token: &str) -> Option<CardVal> {
+    let value = match token.to_lowercase().as_str() {
+        "j" | "bube" => Some(CardVal::Jack),
+        "q" | "dame" => Some(CardVal::Queen),
+        "k" | "koenig" => Some(CardVal::King),
+        "t" | "10" | "zehn" => Some(CardVal::Ten),
+        "a" | "ass" | "as" => Some(CardVal::Ace),
+        _ => None,
+    };
+    value.or_else(|| CardVal::from_name(token))
+}
+
+impl FromStr for CardSuit {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_suit(s.trim()).ok_or_else(|| NotationError::InvalidSuit(s.to_string()))
+    }
+}
+
+impl FromStr for CardVal {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_value(s.trim()).ok_or_else(|| NotationError::InvalidValue(s.to_string()))
+    }
+}
+
+/// Accepts the compact form (`HA`, `♠K`, `D10`) as well as spelled out cards (`Herz Ass`,
+/// `Hearts Ace`, `Ace of Hearts`).
+impl FromStr for Card {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || NotationError::InvalidCard(s.to_string());
+        let words: Vec<_> = s.split_whitespace().collect();
+
+        let (suit, value) = match words.as_slice() {
+            [compact] => {
+                let split = compact.chars().next().ok_or_else(invalid)?.len_utf8();
+                compact.split_at(split)
+            }
+            [suit, value] => (*suit, *value),
+            [value, of, suit] if of.eq_ignore_ascii_case("of") => (*suit, *value),
+            _ => return Err(invalid()),
+        };
+
+        match (parse_suit(suit), parse_value(value)) {
+            (Some(suit), Some(value)) => Ok(Card::new(value, suit)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl FromStr for AnnounceType {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "20" => Ok(AnnounceType::Twenty),
+            "40" => Ok(AnnounceType::Forty),
+            _ => Err(NotationError::InvalidAnnouncement(s.to_string())),
+        }
+    }
+}
+
+/// Accepts `20 H`, `20H` and `40 ♠`.
+impl FromStr for Announcement {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || NotationError::InvalidAnnouncement(s.to_string());
+        if s.len() < 2 || !s.is_char_boundary(2) {
+            return Err(invalid());
+        }
+
+        let (announce_type, suit) = s.split_at(2);
+        let announce_type = announce_type.parse().map_err(|_| invalid())?;
+        let suit = parse_suit(suit.trim()).ok_or_else(invalid)?;
+        Ok(Announcement::new(announce_type, suit))
+    }
+}
+
+impl FromStr for Action {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || NotationError::InvalidAction(s.to_string());
+        let (command, argument) = match s.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (s, ""),
+        };
+
+        let count = || argument.parse::<usize>().map_err(|_| invalid());
+        let card = || argument.parse::<Card>().map_err(|_| invalid());
+
+        match (command.to_lowercase().as_str(), argument.is_empty()) {
+            ("cut", false) => Ok(Action::CuttDeck(count()?)),
+            ("take", false) => Ok(Action::TakeCards(count()?)),
+            ("play", false) => Ok(Action::PlayCard(card()?)),
+            ("swap", false) => Ok(Action::SwapTrump(card()?)),
+            ("close", true) => Ok(Action::CloseTalon),
+            ("40", true) => Ok(Action::Announce40),
+            _ if s.starts_with("20") => {
+                let announcement: Announcement = s.parse().map_err(|_| invalid())?;
+                Ok(Action::Announce20(announcement.cards))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub player: String,
+    pub action: Action,
+}
+
+/// A complete game in the line based notation described in the module documentation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameRecord {
+    pub players: [String; 2],
+    pub hands: [Vec<Card>; 2],
+    pub trump: Option<Card>,
+    /// The remaining deck after the deal, with the next card to be drawn first.
+    pub talon: Vec<Card>,
+    pub moves: Vec<Move>,
+}
+
+impl GameRecord {
+    pub fn new(players: [String; 2]) -> Self {
+        Self {
+            players,
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, player: &str, action: Action) {
+        self.moves.push(Move {
+            player: player.to_string(),
+            action,
+        });
+    }
+
+    fn player_index(&self, player: &str) -> Result<usize, NotationError> {
+        if self.players.iter().all(String::is_empty) {
+            return Err(NotationError::MissingPlayers);
+        }
+        self.players
+            .iter()
+            .position(|known| known == player)
+            .ok_or_else(|| NotationError::UnknownPlayer(player.to_string()))
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), NotationError> {
+        let parse_cards = |cards: &str| {
+            cards
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<Card>, _>>()
+        };
+
+        let invalid = || NotationError::InvalidStatement(line.to_string());
+
+        // A quoted name is never a keyword
+        let keyword = match line.starts_with('"') {
+            true => "",
+            false => line.split(char::is_whitespace).next().unwrap_or_default(),
+        };
+        let rest = line[keyword.len()..].trim();
+
+        match keyword {
+            "players" => {
+                let (first, rest) = parse_name(rest)?;
+                let (second, rest) = parse_name(rest)?;
+                if !rest.is_empty() {
+                    return Err(invalid());
+                }
+                self.players = [first, second];
+            }
+            "deal" => {
+                let (player, cards) = parse_name(rest)?;
+                let idx = self.player_index(&player)?;
+                self.hands[idx] = parse_cards(cards)?;
+            }
+            "trump" => self.trump = Some(rest.parse()?),
+            "talon" => self.talon = parse_cards(rest)?,
+            _ => {
+                let (player, action) = parse_name(line)?;
+                self.player_index(&player)?;
+                if action.is_empty() {
+                    return Err(invalid());
+                }
+                self.push(&player, action.parse()?);
+            }
+        }
+        Ok(())
+    }
+}
+
+const KEYWORDS: [&str; 4] = ["players", "deal", "trump", "talon"];
+
+/// The name, in quotes if it would otherwise be read as something else.
+struct Name<'a>(&'a str);
+
+impl fmt::Display for Name<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quoted = KEYWORDS.contains(&self.0)
+            || self
+                .0
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '#' | '"' | '\\'));
+        if !quoted {
+            return f.write_str(self.0);
+        }
+
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+/// Reads the name `text` starts with, quoted or up to the first whitespace, and returns it with
+/// what follows it.
+fn parse_name(text: &str) -> Result<(String, &str), NotationError> {
+    let invalid = || NotationError::InvalidStatement(text.to_string());
+
+    let Some(quoted) = text.strip_prefix('"') else {
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        if end == 0 {
+            return Err(invalid());
+        }
+        return Ok((text[..end].to_string(), text[end..].trim_start()));
+    };
+
+    let mut name = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => return Ok((name, quoted[idx + 1..].trim_start())),
+            '\\' => match chars.next() {
+                Some((_, '"')) => name.push('"'),
+                Some((_, '\\')) => name.push('\\'),
+                Some((_, 'n')) => name.push('\n'),
+                Some((_, 'r')) => name.push('\r'),
+                _ => return Err(invalid()),
+            },
+            c => name.push(c),
+        }
+    }
+    Err(invalid())
+}
+
+/// The line without its comment, a `#` in a quoted name doesn't start one.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write_cards = |f: &mut fmt::Formatter<'_>, cards: &[Card]| {
+            cards.iter().try_for_each(|card| write!(f, " {card}"))
+        };
+
+        writeln!(
+            f,
+            "players {} {}",
+            Name(&self.players[0]),
+            Name(&self.players[1])
+        )?;
+
+        let (setup, moves): (Vec<_>, Vec<_>) = self
+            .moves
+            .iter()
+            .partition(|step| matches!(step.action, Action::CuttDeck(_) | Action::TakeCards(_)));
+        for step in setup {
+            writeln!(f, "{} {}", Name(&step.player), step.action)?;
+        }
+
+        for (player, hand) in self.players.iter().zip(self.hands.iter()) {
+            if !hand.is_empty() {
+                write!(f, "deal {}", Name(player))?;
+                write_cards(f, hand)?;
+                writeln!(f)?;
+            }
+        }
+        if let Some(trump) = self.trump {
+            writeln!(f, "trump {trump}")?;
+        }
+        if !self.talon.is_empty() {
+            write!(f, "talon")?;
+            write_cards(f, &self.talon)?;
+            writeln!(f)?;
+        }
+
+        for step in moves {
+            writeln!(f, "{} {}", Name(&step.player), step.action)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut record = GameRecord::default();
+        for (idx, line) in s.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            record
+                .parse_line(line)
+                .map_err(|error| NotationError::Line {
+                    line: idx + 1,
+                    error: Box::new(error),
+                })?;
+        }
+
+        if record.players.iter().any(String::is_empty) {
+            return Err(NotationError::MissingPlayers);
+        }
+        Ok(record)
+    }
+}
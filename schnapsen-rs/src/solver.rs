@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::{
+    models::{Action, Card, CardSet, CardSuit},
+    state::{GameState, RoundResult},
+};
+
+/// The best action of the player to move and the outcome both players can force with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    pub action: Action,
+    pub result: RoundResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    value: i8,
+    bound: Bound,
+    action: Option<Action>,
+}
+
+/// Everything about a position without a talon which still matters for the rest of the round.
+/// Positions reached through different orders of the same tricks share a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    hands: [CardSet; 2],
+    won_points: [u8; 2],
+    has_won: [bool; 2],
+    announced_points: [u8; 2],
+    announced: CardSet,
+    trump: CardSuit,
+    closed_by: Option<usize>,
+    to_move: usize,
+    lead: Option<Card>,
+    must_play: CardSet,
+}
+
+impl Key {
+    fn new(state: &GameState) -> Self {
+        Self {
+            hands: state.hands,
+            won_points: state
+                .won
+                .map(|won| won.iter().map(|card| card.points()).sum()),
+            has_won: state.won.map(|won| !won.is_empty()),
+            announced_points: state.announced_points,
+            announced: state.announced,
+            trump: state.trump,
+            closed_by: state.closed_by,
+            to_move: state.to_move,
+            lead: state.lead,
+            must_play: state.must_play,
+        }
+    }
+}
+
+/// Searched positions are forgotten once the table grows beyond this many entries.
+const MAX_ENTRIES: usize = 1 << 20;
+
+/// Alpha-beta search for positions where nothing is left to draw, because the talon is either
+/// closed or exhausted. Once a player counted the cards, such a position has perfect information.
+/// Positions are evaluated in game points won by player `0`, so player `0` maximizes and player
+/// `1` minimizes. Searched positions are kept between calls, so reusing a `Solver` within a round
+/// gets cheaper with every trick.
+#[derive(Debug, Default)]
+pub struct Solver {
+    table: HashMap<Key, Entry>,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `None` if the round is already finished or cards can still be drawn.
+    pub fn solve(&mut self, state: &GameState) -> Option<Solution> {
+        if state.is_terminal() || !state.is_talon_exhausted() {
+            return None;
+        }
+        if self.table.len() > MAX_ENTRIES {
+            self.clear();
+        }
+        let (value, action) = self.search(state, i8::MIN, i8::MAX);
+        Some(Solution {
+            action: action?,
+            result: RoundResult {
+                winner: usize::from(value < 0),
+                game_points: value.unsigned_abs(),
+            },
+        })
+    }
+
+    /// Forgets all searched positions.
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    #[inline]
+    fn value(result: RoundResult) -> i8 {
+        let points = result.game_points as i8;
+        if result.winner == 0 {
+            points
+        } else {
+            -points
+        }
+    }
+
+    fn search(&mut self, state: &GameState, mut alpha: i8, mut beta: i8) -> (i8, Option<Action>) {
+        if let Some(result) = state.result {
+            return (Self::value(result), None);
+        }
+
+        let key = Key::new(state);
+        let (original_alpha, original_beta) = (alpha, beta);
+        let mut actions = state.legal_actions();
+
+        if let Some(entry) = self.table.get(&key) {
+            match entry.bound {
+                Bound::Exact => return (entry.value, entry.action),
+                Bound::Lower => alpha = alpha.max(entry.value),
+                Bound::Upper => beta = beta.min(entry.value),
+            }
+            if alpha >= beta {
+                return (entry.value, entry.action);
+            }
+            // The best action of an earlier search most likely causes a cutoff again
+            if let Some(position) = actions
+                .iter()
+                .position(|action| Some(*action) == entry.action)
+            {
+                actions.swap(0, position);
+            }
+        }
+
+        let maximizing = state.to_move == 0;
+        let mut best = if maximizing { i8::MIN } else { i8::MAX };
+        let mut best_action = None;
+
+        for action in actions {
+            let mut next = state.clone();
+            next.apply(action).expect("legal actions can be applied");
+            let (value, _) = self.search(&next, alpha, beta);
+
+            if maximizing {
+                if value > best {
+                    best = value;
+                    best_action = Some(action);
+                }
+                alpha = alpha.max(best);
+            } else {
+                if value < best {
+                    best = value;
+                    best_action = Some(action);
+                }
+                beta = beta.min(best);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= original_beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(
+            key,
+            Entry {
+                value: best,
+                bound,
+                action: best_action,
+            },
+        );
+        (best, best_action)
+    }
+}
@@ -0,0 +1,368 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    agent::Observation,
+    models::{Action, AnnounceType, Card, CardSet, CardSuit, CardVal},
+    PlayerError,
+};
+
+/// Outcome of a finished round, seen from the players of a `GameState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RoundResult {
+    pub winner: usize,
+    pub game_points: u8,
+}
+
+/// A cheap, cloneable copy of a single round which follows the same rules as `SchnapsenDuo`,
+/// without players, locks or callbacks. Players are referred to by their index `0` or `1`.
+/// Used by bots to simulate the rest of a round.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameState {
+    pub hands: [CardSet; 2],
+    pub won: [CardSet; 2],
+    /// Points of the announcements of each player, which only count once they won a trick.
+    pub announced_points: [u8; 2],
+    /// The cards of every announcement so far.
+    pub announced: CardSet,
+    /// The face-down talon, where the last card is drawn next.
+    pub talon: Vec<Card>,
+    pub trump: CardSuit,
+    pub trump_card: Option<Card>,
+    pub closed_by: Option<usize>,
+    pub to_move: usize,
+    /// The card led in the current trick.
+    pub lead: Option<Card>,
+    /// After an announcement, one of the announced cards has to be played.
+    pub must_play: CardSet,
+    pub result: Option<RoundResult>,
+}
+
+impl GameState {
+    /// A freshly dealt round, where `talon` is drawn from its end and `leader` plays first.
+    pub fn new(hands: [CardSet; 2], trump_card: Card, talon: Vec<Card>, leader: usize) -> Self {
+        Self {
+            hands,
+            won: [CardSet::EMPTY; 2],
+            announced_points: [0; 2],
+            announced: CardSet::EMPTY,
+            talon,
+            trump: trump_card.suit,
+            trump_card: Some(trump_card),
+            closed_by: None,
+            to_move: leader,
+            lead: None,
+            must_play: CardSet::EMPTY,
+            result: None,
+        }
+    }
+
+    /// Samples a state which is consistent with everything the observing player knows, by dealing
+    /// the unseen cards randomly between the opponent's hand and the talon. Cards the opponent
+    /// revealed always end up in their hand and cards they can't hold in the talon. The observing
+    /// player always has index `0`.
+    pub fn sample<R: Rng + ?Sized>(observation: &Observation, rng: &mut R) -> Self {
+        let unseen = observation.unseen();
+        let announced: CardSet = observation
+            .announcements
+            .iter()
+            .chain(observation.opponent_announcements.iter())
+            .flat_map(|announcement| announcement.cards)
+            .collect();
+        let opponent_announced: CardSet = observation
+            .opponent_announcements
+            .iter()
+            .flat_map(|announcement| announcement.cards)
+            .collect();
+
+        let known = (opponent_announced | observation.opponent_revealed) & unseen;
+        let ruled_out = (unseen - known) & observation.opponent_ruled_out;
+        let mut candidates = (unseen - known - ruled_out).to_vec();
+        candidates.shuffle(rng);
+
+        let missing = observation
+            .opponent_hand_size
+            .saturating_sub(known.len() as usize);
+        let mut talon = candidates.split_off(missing.min(candidates.len()));
+        let mut opponent_hand = known | candidates.into_iter().collect();
+
+        talon.extend(ruled_out);
+        talon.shuffle(rng);
+        // Only happens if the observation contradicts itself
+        while (opponent_hand.len() as usize) < observation.opponent_hand_size {
+            let Some(card) = talon.pop() else {
+                break;
+            };
+            opponent_hand.insert(card);
+        }
+
+        let points = |announcements: &[crate::models::Announcement]| {
+            announcements
+                .iter()
+                .map(|announcement| announcement.announce_type as u8)
+                .sum()
+        };
+
+        let must_play = if observation.lead.is_none() && observation.playable != observation.hand {
+            observation.playable
+        } else {
+            CardSet::EMPTY
+        };
+
+        Self {
+            hands: [observation.hand, opponent_hand],
+            won: [observation.won, observation.opponent_won],
+            announced_points: [
+                points(&observation.announcements),
+                points(&observation.opponent_announcements),
+            ],
+            announced,
+            talon,
+            trump: observation.trump,
+            trump_card: observation.trump_card,
+            closed_by: observation
+                .closed_talon
+                .as_ref()
+                .map(|id| usize::from(*id != observation.player_id)),
+            to_move: 0,
+            lead: observation.lead,
+            must_play,
+            result: None,
+        }
+    }
+
+    #[inline]
+    pub fn is_terminal(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Whether no more cards can be drawn, because the talon is closed or empty.
+    #[inline]
+    pub fn is_talon_exhausted(&self) -> bool {
+        self.closed_by.is_some() || self.trump_card.is_none()
+    }
+
+    /// Points of the won tricks, including announcements once a trick was won.
+    #[inline]
+    pub fn trick_points(&self, player: usize) -> u8 {
+        let tricks: u8 = self.won[player].iter().map(|card| card.points()).sum();
+        if self.won[player].is_empty() {
+            tricks
+        } else {
+            tricks + self.announced_points[player]
+        }
+    }
+
+    /// The cards the player to move is allowed to play.
+    pub fn playable(&self) -> CardSet {
+        let hand = self.hands[self.to_move];
+        let Some(lead) = self.lead else {
+            return if self.must_play.is_empty() {
+                hand
+            } else {
+                self.must_play
+            };
+        };
+
+        let mut playable = hand;
+        if self.is_talon_exhausted() {
+            let follow = hand.of_suit(lead.suit);
+            playable = if follow.is_empty() {
+                hand.of_suit(self.trump)
+            } else {
+                follow
+            };
+        }
+        if playable.is_empty() {
+            playable = hand;
+        }
+
+        if self.closed_by.is_some() {
+            let winning: CardSet = playable
+                .iter()
+                .filter(|card| card.beats(&lead, self.trump))
+                .collect();
+            if !winning.is_empty() {
+                playable = winning;
+            }
+        }
+        playable
+    }
+
+    /// The marriages of the player to move which have not been announced yet.
+    fn marriages(&self) -> impl Iterator<Item = CardSuit> + '_ {
+        let hand = self.hands[self.to_move];
+        CardSuit::ALL.into_iter().filter(move |suit| {
+            let pair = hand.of_suit(*suit)
+                & (CardSet::value(CardVal::Queen) | CardSet::value(CardVal::King));
+            pair.len() == 2 && (pair & self.announced).is_empty()
+        })
+    }
+
+    #[inline]
+    fn trump_jack(&self) -> Option<Card> {
+        let jack = Card::new(CardVal::Jack, self.trump);
+        (self.trump_card.is_some()
+            && self.closed_by.is_none()
+            && self.hands[self.to_move].contains(jack))
+        .then_some(jack)
+    }
+
+    /// All actions of the player to move, the same as `SchnapsenDuo::legal_actions` would return.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if self.is_terminal() {
+            return Vec::new();
+        }
+
+        let mut actions: Vec<_> = self.playable().iter().map(Action::PlayCard).collect();
+        if self.lead.is_some() || !self.must_play.is_empty() {
+            return actions;
+        }
+
+        for suit in self.marriages() {
+            if suit == self.trump {
+                actions.push(Action::Announce40);
+            } else {
+                actions.push(Action::Announce20([
+                    Card::new(CardVal::Queen, suit),
+                    Card::new(CardVal::King, suit),
+                ]));
+            }
+        }
+
+        if let Some(jack) = self.trump_jack() {
+            actions.push(Action::SwapTrump(jack));
+        }
+
+        if self.closed_by.is_none() && !self.talon.is_empty() {
+            actions.push(Action::CloseTalon);
+        }
+        actions
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<(), PlayerError> {
+        if self.is_terminal() {
+            return Err(PlayerError::NoPlayerActive);
+        }
+        let player = self.to_move;
+
+        match action {
+            Action::PlayCard(card) => {
+                if !self.playable().contains(card) {
+                    return Err(PlayerError::CantPlayCard(card));
+                }
+                self.hands[player].remove(card);
+                self.must_play = CardSet::EMPTY;
+
+                match self.lead.take() {
+                    None => {
+                        self.lead = Some(card);
+                        self.to_move = 1 - player;
+                    }
+                    Some(lead) => self.finish_trick(lead, card),
+                }
+            }
+            Action::Announce20(cards) => {
+                let suit = cards[0].suit;
+                if suit == self.trump || !self.marriages().any(|other| other == suit) {
+                    return Err(PlayerError::CantPlay20);
+                }
+                self.announce(suit, AnnounceType::Twenty);
+            }
+            Action::Announce40 => {
+                if !self.marriages().any(|suit| suit == self.trump) {
+                    return Err(PlayerError::CantPlay40);
+                }
+                self.announce(self.trump, AnnounceType::Forty);
+            }
+            Action::SwapTrump(card) => {
+                if self.trump_jack() != Some(card) || self.lead.is_some() {
+                    return Err(PlayerError::CantSwapTrump);
+                }
+                let trump_card = self.trump_card.replace(card).unwrap();
+                self.hands[player].remove(card);
+                self.hands[player].insert(trump_card);
+            }
+            Action::CloseTalon => {
+                if self.closed_by.is_some() {
+                    return Err(PlayerError::TalonAlreadyClosed);
+                }
+                if self.talon.is_empty() || self.lead.is_some() {
+                    return Err(PlayerError::PlayerNotActive);
+                }
+                self.closed_by = Some(player);
+            }
+            Action::CuttDeck(_) | Action::TakeCards(_) => return Err(PlayerError::PlayerNotActive),
+        }
+        Ok(())
+    }
+
+    fn announce(&mut self, suit: CardSuit, announce_type: AnnounceType) {
+        let pair = self.hands[self.to_move].of_suit(suit)
+            & (CardSet::value(CardVal::Queen) | CardSet::value(CardVal::King));
+        self.announced |= pair;
+        self.must_play = pair;
+        self.announced_points[self.to_move] += announce_type as u8;
+        self.update_finished(self.to_move);
+    }
+
+    fn finish_trick(&mut self, lead: Card, response: Card) {
+        let leader = 1 - self.to_move;
+        let winner = if response.beats(&lead, self.trump) {
+            self.to_move
+        } else {
+            leader
+        };
+
+        self.won[winner].insert(lead);
+        self.won[winner].insert(response);
+        self.to_move = winner;
+
+        if self.update_finished(winner) {
+            return;
+        }
+
+        if self.closed_by.is_none() && self.trump_card.is_some() {
+            for player in [winner, 1 - winner] {
+                let card = match self.talon.pop() {
+                    Some(card) => card,
+                    None => self.trump_card.take().unwrap(),
+                };
+                self.hands[player].insert(card);
+            }
+        }
+    }
+
+    /// Mirrors the end of round rules of `SchnapsenDuo`. `last` is the player who took the last
+    /// trick or announced.
+    fn update_finished(&mut self, last: usize) -> bool {
+        let points = [self.trick_points(0), self.trick_points(1)];
+        let mut winner = if points[0] > points[1] { 0 } else { 1 };
+
+        if points[winner] < 66 {
+            if !self.hands.iter().any(|hand| hand.is_empty()) {
+                return false;
+            }
+            match self.closed_by {
+                Some(closer) if closer == winner => winner = 1 - winner,
+                Some(_) => {}
+                None if last != winner => winner = 1 - winner,
+                None => {}
+            }
+        }
+
+        let loser_points = points[0].min(points[1]);
+        let game_points = if loser_points == 0 {
+            3
+        } else if loser_points <= 33 {
+            2
+        } else {
+            1
+        };
+
+        self.result = Some(RoundResult {
+            winner,
+            game_points,
+        });
+        true
+    }
+}
@@ -0,0 +1,149 @@
+//! Statistics of the games between two agents, as reported by `schnapsen-sim`.
+
+use serde::Serialize;
+
+use crate::{models::Action, notation::GameRecord};
+
+/// A proportion with its 95% Wilson score interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Rate {
+    pub rate: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Rate {
+    /// Without any trials, the rate is `0` and could be anything.
+    pub fn new(successes: usize, trials: usize) -> Self {
+        if trials == 0 {
+            return Self {
+                rate: 0.0,
+                lower: 0.0,
+                upper: 1.0,
+            };
+        }
+        const Z: f64 = 1.96;
+        let n = trials as f64;
+        let p = successes as f64 / n;
+        let center = (p + Z * Z / (2.0 * n)) / (1.0 + Z * Z / n);
+        let margin = Z / (1.0 + Z * Z / n) * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt();
+        Self {
+            rate: p,
+            lower: (center - margin).max(0.0),
+            upper: (center + margin).min(1.0),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Tally {
+    rounds_won: usize,
+    bummerls_won: usize,
+    game_points: usize,
+    closed_talon: usize,
+    announcements: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgentReport {
+    pub agent: String,
+    pub rounds_won: usize,
+    pub round_win_rate: Rate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bummerls_won: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bummerl_win_rate: Option<Rate>,
+    /// Game points won per round played.
+    pub average_game_points: f64,
+    /// Share of the rounds in which the agent closed the talon.
+    pub close_talon_rate: f64,
+    /// Announcements per round played.
+    pub announcement_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub seed: u64,
+    pub rounds: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bummerls: Option<usize>,
+    pub agents: [AgentReport; 2],
+}
+
+/// Counts the rounds and bummerls of two agents, which are the players with the ids `"0"` and
+/// `"1"` of every game.
+#[derive(Debug, Default, Clone)]
+pub struct Statistics {
+    tallies: [Tally; 2],
+    rounds: usize,
+    bummerls: usize,
+}
+
+impl Statistics {
+    pub const IDS: [&'static str; 2] = ["0", "1"];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The index of the agent playing as `player_id`.
+    #[inline]
+    pub fn agent_of(player_id: &str) -> Option<usize> {
+        Self::IDS.iter().position(|id| *id == player_id)
+    }
+
+    /// Counts a finished round, which was won by `winner` with the given game points.
+    pub fn count_round(&mut self, record: &GameRecord, winner: usize, game_points: u8) {
+        self.rounds += 1;
+        self.tallies[winner].rounds_won += 1;
+        self.tallies[winner].game_points += game_points as usize;
+
+        for step in &record.moves {
+            let Some(agent) = Self::agent_of(&step.player) else {
+                continue;
+            };
+            let tally = &mut self.tallies[agent];
+            match step.action {
+                Action::CloseTalon => tally.closed_talon += 1,
+                Action::Announce20(_) | Action::Announce40 => tally.announcements += 1,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn count_bummerl(&mut self, winner: usize) {
+        self.bummerls += 1;
+        self.tallies[winner].bummerls_won += 1;
+    }
+
+    /// The report of the agents with the given names, with the bummerls only if they were played.
+    pub fn report(&self, agents: &[String; 2], seed: u64, bummerls: bool) -> Report {
+        let rounds = self.rounds;
+        let per_round = |count: usize| {
+            if rounds == 0 {
+                0.0
+            } else {
+                count as f64 / rounds as f64
+            }
+        };
+        let agents = [0, 1].map(|idx| {
+            let tally = &self.tallies[idx];
+            AgentReport {
+                agent: agents[idx].clone(),
+                rounds_won: tally.rounds_won,
+                round_win_rate: Rate::new(tally.rounds_won, rounds),
+                bummerls_won: bummerls.then_some(tally.bummerls_won),
+                bummerl_win_rate: bummerls.then(|| Rate::new(tally.bummerls_won, self.bummerls)),
+                average_game_points: per_round(tally.game_points),
+                close_talon_rate: per_round(tally.closed_talon),
+                announcement_rate: per_round(tally.announcements),
+            }
+        });
+        Report {
+            seed,
+            rounds,
+            bummerls: bummerls.then_some(self.bummerls),
+            agents,
+        }
+    }
+}
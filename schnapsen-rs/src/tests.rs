@@ -9,7 +9,9 @@ fn test_create_instance() {
     assert_eq!(instance.deck.len(), 20);
     assert_eq!(instance.players.len(), 2);
 
-    instance.set_active_player(instance.players[0].clone()).unwrap();
+    instance
+        .set_active_player(instance.players[0].clone())
+        .unwrap();
     instance.distribute_cards().unwrap();
 
     let player1 = instance.players[0].read().unwrap();
@@ -25,30 +27,198 @@ fn test_create_instance() {
 #[test]
 fn test_play_card_allowed() {}
 
-pub mod helpers {
-    use std::{collections::HashSet, hash::Hash};
+#[test]
+fn test_card_index_roundtrip() {
+    for index in 0..20 {
+        let card = Card::from_index(index).unwrap();
+        assert_eq!(card.index(), index);
+    }
+    assert!(Card::from_index(20).is_none());
+}
 
-    use crate::models::{Card, CardSuit, CardVal};
+#[test]
+fn test_card_set_operations() {
+    use models::{CardSet, CardSuit, CardVal};
 
-    impl Hash for Card {
-        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-            self.value.hash(state);
-            self.suit.hash(state);
-        }
+    let queen = Card::new(CardVal::Queen, CardSuit::Hearts);
+    let king = Card::new(CardVal::King, CardSuit::Hearts);
+    let ace = Card::new(CardVal::Ace, CardSuit::Spades);
+
+    let hand: CardSet = [queen, king, ace].into_iter().collect();
+    assert_eq!(hand.len(), 3);
+    assert!(hand.contains(ace));
+    assert_eq!(hand.of_suit(CardSuit::Hearts).to_vec(), vec![queen, king]);
+    assert!(hand.of_suit(CardSuit::Clubs).is_empty());
+    assert_eq!(hand & CardSet::value(CardVal::Ace), CardSet::single(ace));
+    assert_eq!((hand - CardSet::single(ace)) | CardSet::single(ace), hand);
+    assert_eq!(hand.complement().len(), 17);
+    assert_eq!(CardSet::FULL.iter().count(), 20);
+    assert!(CardSuit::ALL
+        .into_iter()
+        .all(|suit| CardSet::suit(suit).len() == 5));
+}
+
+#[test]
+fn test_card_set_serde_matches_vec() {
+    use models::CardSet;
+
+    let cards: Vec<Card> = (0..20).step_by(3).filter_map(Card::from_index).collect();
+    let set = CardSet::from(cards.clone());
+
+    let json = serde_json::to_string(&set).unwrap();
+    assert_eq!(json, serde_json::to_string(&cards).unwrap());
+    assert_eq!(serde_json::from_str::<CardSet>(&json).unwrap(), set);
+}
+
+#[test]
+fn announcements_count_once_a_trick_is_won() {
+    use models::{AnnounceType, CardSuit::*, CardVal::*};
+
+    let instance = SchnapsenDuo::new(&["1".to_string(), "2".to_string()]);
+    let [first, second] = instance.players.clone();
+    first.write().unwrap().announcements.push(Announcement {
+        cards: [Card::new(Queen, Clubs), Card::new(King, Clubs)],
+        announce_type: AnnounceType::Forty,
+    });
+    second
+        .write()
+        .unwrap()
+        .tricks
+        .push([Card::new(Jack, Hearts), Card::new(Queen, Hearts)]);
+
+    let points = instance.calc_points().unwrap();
+    assert_eq!(points.winner.player.read().unwrap().id, "2");
+    assert_eq!((points.winner.points, points.loser.points), (5, 0));
+
+    first
+        .write()
+        .unwrap()
+        .tricks
+        .push([Card::new(Jack, Spades), Card::new(Ten, Spades)]);
+
+    let points = instance.calc_points().unwrap();
+    assert_eq!(points.winner.player.read().unwrap().id, "1");
+    assert_eq!((points.winner.points, points.loser.points), (52, 5));
+}
+
+mod card_ordering {
+    use std::{
+        cmp::Ordering,
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    use proptest::prelude::*;
+
+    use crate::models::{contains_card_comb, Card, CardSuit, CardVal};
+
+    fn card() -> impl Strategy<Value = Card> {
+        (0u8..20).prop_map(|index| Card::from_index(index).unwrap())
     }
 
-    impl Hash for CardVal {
-        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-            state.write_u8(self.clone() as u8);
-        }
+    fn suit() -> impl Strategy<Value = CardSuit> {
+        (0u8..4).prop_map(CardSuit::from)
+    }
+
+    fn hash_of(card: &Card) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        card.hash(&mut hasher);
+        hasher.finish()
     }
 
-    impl Hash for CardSuit {
-        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-            state.write_u8(self.clone() as u8);
+    proptest! {
+        #[test]
+        fn ord_agrees_with_eq(a in card(), b in card()) {
+            prop_assert_eq!(a.cmp(&b) == Ordering::Equal, a == b);
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            prop_assert_eq!(a.cmp(&b), a.index().cmp(&b.index()));
+        }
+
+        #[test]
+        fn hash_agrees_with_eq(a in card(), b in card()) {
+            if a == b {
+                prop_assert_eq!(hash_of(&a), hash_of(&b));
+            }
+        }
+
+        #[test]
+        fn sorting_ignores_input_order(mut hand in proptest::sample::subsequence((0u8..20).collect::<Vec<_>>(), 0..=20).prop_shuffle()) {
+            let mut cards: Vec<Card> = hand.iter().map(|index| Card::from_index(*index).unwrap()).collect();
+            cards.sort();
+            hand.sort();
+            prop_assert_eq!(cards.iter().map(Card::index).collect::<Vec<_>>(), hand);
+        }
+
+        #[test]
+        fn rank_order_is_total(a in card(), b in card(), c in card()) {
+            prop_assert_eq!(a.cmp_by_rank(&b) == Ordering::Equal, a == b);
+            prop_assert_eq!(a.cmp_by_rank(&b), b.cmp_by_rank(&a).reverse());
+            if a.cmp_by_rank(&b) != Ordering::Greater && b.cmp_by_rank(&c) != Ordering::Greater {
+                prop_assert_ne!(a.cmp_by_rank(&c), Ordering::Greater);
+            }
+        }
+
+        #[test]
+        fn trick_order_is_total(a in card(), b in card(), c in card(), trump in suit(), led in suit()) {
+            let cmp = |x: &Card, y: &Card| x.cmp_in_trick(y, trump, led);
+            prop_assert_eq!(cmp(&a, &b) == Ordering::Equal, a == b);
+            prop_assert_eq!(cmp(&a, &b), cmp(&b, &a).reverse());
+            if cmp(&a, &b) != Ordering::Greater && cmp(&b, &c) != Ordering::Greater {
+                prop_assert_ne!(cmp(&a, &c), Ordering::Greater);
+            }
+        }
+
+        #[test]
+        fn trick_winner_follows_rules(led in card(), response in card(), trump in suit()) {
+            prop_assume!(led != response);
+            let expected = if response.suit == led.suit {
+                response.value > led.value
+            } else {
+                response.suit == trump
+            };
+            prop_assert_eq!(response.beats(&led, trump), expected);
+        }
+
+        #[test]
+        fn card_combination_ignores_order(a in card(), b in card()) {
+            prop_assert!(contains_card_comb(&[[a, b]], [b, a]));
         }
     }
 
+    #[test]
+    fn hash_is_unique_per_card() {
+        let hashes: std::collections::HashSet<_> = (0..20)
+            .map(|index| hash_of(&Card::from_index(index).unwrap()))
+            .collect();
+        assert_eq!(hashes.len(), 20);
+    }
+
+    #[test]
+    fn announcement_pairs_compare_across_suits() {
+        let hearts = [
+            Card::new(CardVal::King, CardSuit::Hearts),
+            Card::new(CardVal::Queen, CardSuit::Hearts),
+        ];
+        let spades = [
+            Card::new(CardVal::Queen, CardSuit::Spades),
+            Card::new(CardVal::King, CardSuit::Spades),
+        ];
+        assert!(!contains_card_comb(&[hearts], spades));
+        assert!(contains_card_comb(
+            &[hearts, spades],
+            [spades[1], spades[0]]
+        ));
+    }
+}
+
+pub mod helpers {
+    use std::{collections::HashSet, hash::Hash};
+
+    use crate::knowledge::KnowledgeTracker;
+    use crate::models::{Card, CardSet, CardSuit, CardVal};
+    use crate::state::GameState;
+    use crate::{PrivateEvent, PublicEvent, SchnapsenDuo};
+
     pub fn has_no_repeating_elements<T>(iter: T) -> bool
     where
         T: IntoIterator,
@@ -57,4 +227,1241 @@ pub mod helpers {
         let mut uniq = HashSet::new();
         iter.into_iter().all(move |x| uniq.insert(x))
     }
+
+    /// The card in the notation of `crate::notation`, e.g. `HA`.
+    pub fn card(card: &str) -> Card {
+        card.parse().unwrap()
+    }
+
+    /// The cards separated by whitespace, in the order they are written.
+    pub fn card_vec(cards: &str) -> Vec<Card> {
+        cards.split_whitespace().map(card).collect()
+    }
+
+    pub fn cards(cards: &str) -> CardSet {
+        cards.split_whitespace().map(card).collect()
+    }
+
+    /// A round with fixed hands, where the first player leads. The last card of `deck` is drawn
+    /// first.
+    pub fn fixed_round(hands: [&str; 2], trump: &str, deck: &str) -> SchnapsenDuo {
+        let mut instance = SchnapsenDuo::new(&["1".to_string(), "2".to_string()]);
+        for ((player, knowledge), hand) in
+            instance.players.iter().zip(&instance.knowledge).zip(hands)
+        {
+            player.write().unwrap().cards = card_vec(hand);
+            let mut knowledge = knowledge.write().unwrap();
+            for card in cards(hand) {
+                knowledge.on_priv_event(&PrivateEvent::CardAvailabe(card));
+            }
+            knowledge.on_pub_event(&PublicEvent::TrumpChange(Some(self::card(trump))));
+        }
+        instance.deck = card_vec(deck);
+        instance.trump = Some(card(trump));
+        let first = instance.players[0].clone();
+        instance.swap_to(first);
+        instance
+    }
+
+    /// A position after the talon was exhausted, where player `0` leads.
+    pub fn endgame(hands: [&str; 2], won: [&str; 2], trump: CardSuit) -> GameState {
+        let mut state = GameState::new(
+            hands.map(cards),
+            Card::new(CardVal::Jack, trump),
+            Vec::new(),
+            0,
+        );
+        state.trump_card = None;
+        state.won = won.map(cards);
+        state
+    }
+
+    /// A tracker of player "1" after the deal.
+    pub fn dealt(hand: &str, trump: &str) -> KnowledgeTracker {
+        let mut tracker = KnowledgeTracker::new("1");
+        for card in cards(hand) {
+            tracker.on_priv_event(&PrivateEvent::CardAvailabe(card));
+        }
+        tracker.on_pub_event(&PublicEvent::TrumpChange(Some(self::card(trump))));
+        tracker
+    }
+}
+
+mod notation {
+    use proptest::prelude::*;
+
+    use crate::models::{Action, AnnounceType, Announcement, Card, CardSuit, CardVal};
+    use crate::notation::{GameRecord, NotationError};
+
+    #[test]
+    fn card_roundtrip() {
+        for card in (0..20).filter_map(Card::from_index) {
+            assert_eq!(card.to_string().parse::<Card>().unwrap(), card);
+            assert_eq!(format!("{card:#}").parse::<Card>().unwrap(), card);
+        }
+    }
+
+    #[test]
+    fn card_spellings() {
+        let ace = Card::new(CardVal::Ace, CardSuit::Hearts);
+        for text in ["HA", "ha", "♥A", "Herz Ass", "Hearts Ace", "ace of hearts"] {
+            assert_eq!(text.parse::<Card>().unwrap(), ace, "{text}");
+        }
+        assert_eq!(
+            "D10".parse::<Card>().unwrap(),
+            Card::new(CardVal::Ten, CardSuit::Diamonds)
+        );
+        assert!("XA".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn announcement_and_action_roundtrip() {
+        let announcement = Announcement::new(AnnounceType::Forty, CardSuit::Spades);
+        assert_eq!(announcement.to_string(), "40 S");
+        assert_eq!("40♠".parse::<Announcement>().unwrap(), announcement);
+
+        let actions = [
+            Action::CuttDeck(3),
+            Action::TakeCards(4),
+            Action::PlayCard(Card::new(CardVal::Ace, CardSuit::Hearts)),
+            Action::SwapTrump(Card::new(CardVal::Jack, CardSuit::Clubs)),
+            Action::CloseTalon,
+            Action::Announce20(Announcement::new(AnnounceType::Twenty, CardSuit::Hearts).cards),
+            Action::Announce40,
+        ];
+        for action in actions {
+            assert_eq!(action.to_string().parse::<Action>().unwrap(), action);
+        }
+        assert!("play".parse::<Action>().is_err());
+        assert!("40 garbage".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn game_record_roundtrip() {
+        let text = "\
+players alice bob
+alice cut 3
+deal alice HA HK HQ SJ ST
+deal bob DA DK CQ CJ DT
+trump CA
+talon CT HT CK DQ SK SQ HJ SA DJ
+# The opening trick
+alice play HA
+bob play HK
+alice 20 S
+alice play SQ
+bob close
+";
+        let record: GameRecord = text.parse().unwrap();
+        assert_eq!(record.players, ["alice".to_string(), "bob".to_string()]);
+        assert_eq!(record.moves.len(), 6);
+        assert_eq!(record.talon.len(), 9);
+        assert_eq!(record.to_string().parse::<GameRecord>().unwrap(), record);
+    }
+
+    #[test]
+    fn game_record_quotes_names_read_otherwise() {
+        let text = "\
+players \"players\" \"Anna \\\"#1\\\" Maria\" # the names are quoted
+\"players\" cut 3
+deal \"Anna \\\"#1\\\" Maria\" DA DK CQ CJ DT
+\"Anna \\\"#1\\\" Maria\" play DA
+";
+        let record: GameRecord = text.parse().unwrap();
+        assert_eq!(
+            record.players,
+            ["players".to_string(), "Anna \"#1\" Maria".to_string()]
+        );
+        assert_eq!(record.moves[0].player, "players");
+        assert_eq!(record.moves[1].player, "Anna \"#1\" Maria");
+        assert_eq!(record.to_string().parse::<GameRecord>().unwrap(), record);
+
+        assert!("players \"alice bob\n".parse::<GameRecord>().is_err());
+        assert!("players alice bob carol\n".parse::<GameRecord>().is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn game_record_roundtrips_any_names(
+            players in ("(?s).+", "(?s).+").prop_filter("distinct", |(a, b)| a != b),
+        ) {
+            let mut record = GameRecord::new([players.0.clone(), players.1.clone()]);
+            record.hands[1] = (0..5).filter_map(Card::from_index).collect();
+            record.push(&players.0, Action::CuttDeck(3));
+            record.push(&players.1, Action::TakeCards(2));
+            record.push(&players.1, Action::PlayCard(record.hands[1][0]));
+            record.push(&players.0, Action::CloseTalon);
+
+            prop_assert_eq!(record.to_string().parse::<GameRecord>(), Ok(record));
+        }
+    }
+
+    #[test]
+    fn game_record_reports_line() {
+        let err = "players alice bob\ncarol play HA\n"
+            .parse::<GameRecord>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            NotationError::Line {
+                line: 2,
+                error: Box::new(NotationError::UnknownPlayer("carol".to_string())),
+            }
+        );
+    }
+}
+
+mod naming {
+    use serde::{Deserialize, Serialize};
+
+    use crate::models::{
+        AnnounceType, Announcement, Card, CardSet, CardSuit, CardVal, Named, Naming,
+    };
+    use crate::PublicEvent;
+
+    #[test]
+    fn names_parse_back_in_every_naming() {
+        for naming in Naming::ALL {
+            for card in (0..20).filter_map(Card::from_index) {
+                let name = card.named(naming).to_string();
+                assert_eq!(name.parse::<Card>().unwrap(), card, "{name}");
+                assert_eq!(CardSuit::from_name(card.suit.name(naming)), Some(card.suit));
+                assert_eq!(
+                    CardVal::from_name(card.value.name(naming)),
+                    Some(card.value)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn german_names() {
+        let card = Card::new(CardVal::Ace, CardSuit::Clubs);
+        assert_eq!(card.named(Naming::German).to_string(), "Eichel Daus");
+        assert_eq!(
+            card.named(Naming::GermanEnglish).to_string(),
+            "Ace of Acorns"
+        );
+        assert_eq!(Naming::for_locale("de-AT"), Naming::German);
+        assert_eq!(Naming::for_locale("en_US"), Naming::French);
+    }
+
+    #[test]
+    fn serde_accepts_every_naming() {
+        let card = Card::new(CardVal::Jack, CardSuit::Diamonds);
+        for naming in Naming::ALL {
+            let json = serde_json::to_string(&card.named(naming)).unwrap();
+            assert_eq!(serde_json::from_str::<Card>(&json).unwrap(), card, "{json}");
+        }
+        assert_eq!(
+            serde_json::to_string(&card).unwrap(),
+            r#"{"value":"Jack","suit":"Diamonds"}"#
+        );
+    }
+
+    #[test]
+    fn serde_with_german() {
+        #[derive(Serialize, Deserialize)]
+        struct Hint {
+            #[serde(with = "crate::models::naming::german")]
+            card: Card,
+        }
+
+        let hint = Hint {
+            card: Card::new(CardVal::Queen, CardSuit::Spades),
+        };
+        let json = serde_json::to_string(&hint).unwrap();
+        assert_eq!(json, r#"{"card":{"value":"Ober","suit":"Laub"}}"#);
+        assert_eq!(serde_json::from_str::<Hint>(&json).unwrap().card, hint.card);
+    }
+
+    #[test]
+    fn naming_applies_to_containers() {
+        let hearts_ace = Card::new(CardVal::Ace, CardSuit::Hearts);
+        let announcement = Announcement::new(AnnounceType::Twenty, CardSuit::Diamonds);
+        let event = PublicEvent::PlayCard {
+            user_id: "1".to_string(),
+            card: hearts_ace,
+        };
+
+        let json = serde_json::to_string(&Named::new(&event, Naming::German)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"event":"PlayCard","data":{"user_id":"1","card":{"value":"Daus","suit":"Herz"}}}"#
+        );
+        let hand = (CardSet::single(hearts_ace), &announcement);
+        let json = serde_json::to_string(&Named::new(&hand, Naming::GermanEnglish)).unwrap();
+        assert_eq!(
+            json,
+            r#"[[{"value":"Ace","suit":"Hearts"}],{"cards":[{"value":"Ober","suit":"Bells"},{"value":"King","suit":"Bells"}],"announce_type":"Twenty"}]"#
+        );
+        let mut ranked = std::collections::HashMap::new();
+        ranked.insert("1".to_string(), Some(hearts_ace));
+        let json = serde_json::to_string(&Named::new(&ranked, Naming::German)).unwrap();
+        assert_eq!(json, r#"{"1":{"value":"Daus","suit":"Herz"}}"#);
+        assert_eq!(
+            serde_json::to_string(&vec![hearts_ace]).unwrap(),
+            r#"[{"value":"Ace","suit":"Hearts"}]"#
+        );
+
+        #[derive(Serialize)]
+        struct Hand {
+            #[serde(with = "crate::models::naming::german")]
+            cards: Vec<Card>,
+        }
+        let json = serde_json::to_string(&Hand {
+            cards: vec![hearts_ace],
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"cards":[{"value":"Daus","suit":"Herz"}]}"#);
+    }
+
+    #[test]
+    fn named_suits_and_values_display() {
+        assert_eq!(CardSuit::Clubs.named(Naming::German).to_string(), "Eichel");
+        assert_eq!(
+            CardSuit::Clubs.named(Naming::GermanEnglish).to_string(),
+            "Acorns"
+        );
+        assert_eq!(CardVal::Jack.named(Naming::German).to_string(), "Unter");
+        assert_eq!(CardVal::Jack.named(Naming::French).to_string(), "Jack");
+    }
+}
+
+mod client {
+    use std::{
+        sync::{mpsc, Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::client::SchnapsenDuoClient;
+    use crate::models::{AnnounceType, Announcement, Card, CardSuit::*, CardVal::*};
+    use crate::{PrivateEvent, SchnapsenDuo};
+
+    #[test]
+    fn announcing_keeps_the_other_marriages() {
+        let mut instance = SchnapsenDuo::new(&["1".to_string(), "2".to_string()]);
+        let [first, second] = instance.players.clone();
+        first.write().unwrap().cards = vec![
+            Card::new(Queen, Hearts),
+            Card::new(King, Hearts),
+            Card::new(Queen, Clubs),
+            Card::new(King, Clubs),
+            Card::new(Ace, Spades),
+        ];
+        second.write().unwrap().cards = vec![
+            Card::new(Ace, Hearts),
+            Card::new(Jack, Hearts),
+            Card::new(Jack, Spades),
+            Card::new(Queen, Spades),
+            Card::new(Jack, Clubs),
+        ];
+        instance.deck = vec![Card::new(Ten, Hearts), Card::new(Ten, Spades)];
+        instance.trump = Some(Card::new(Ten, Diamonds));
+        instance.swap_to(first.clone());
+        instance.update_announcable_props(first.clone());
+
+        let (tx, rx) = mpsc::channel();
+        instance.on_priv_event(first.clone(), move |event| {
+            let _ = tx.send(event);
+        });
+        let client = SchnapsenDuoClient::new(first.clone(), Arc::new(Mutex::new(instance)));
+        client
+            .announce_20([Card::new(Queen, Hearts), Card::new(King, Hearts)])
+            .unwrap();
+
+        let clubs = Announcement {
+            cards: [Card::new(Queen, Clubs), Card::new(King, Clubs)],
+            announce_type: AnnounceType::Twenty,
+        };
+        assert_eq!(first.read().unwrap().announcable, [clubs]);
+        // The marriage of clubs was announcable before, so it is not offered again
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!rx
+            .try_iter()
+            .any(|event| matches!(event, PrivateEvent::CanAnnounce(_))));
+    }
+}
+
+mod agents {
+    use std::sync::{Arc, Mutex};
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::agent::{
+        play_round, Agent, BeginnerAgent, Budget, GreedyAgent, IsmctsAgent, IsmctsConfig,
+        Observation, RandomAgent,
+    };
+    use crate::client::SchnapsenDuoClient;
+    use crate::models::Action;
+    use crate::notation::GameRecord;
+    use crate::state::GameState;
+    use crate::SchnapsenDuo;
+
+    fn new_round() -> Arc<Mutex<SchnapsenDuo>> {
+        let mut instance = SchnapsenDuo::new(&["1".to_string(), "2".to_string()]);
+        let first = instance.players[0].clone();
+        instance.set_active_player(first).unwrap();
+        instance.distribute_cards().unwrap();
+        Arc::new(Mutex::new(instance))
+    }
+
+    fn wins(first: &mut dyn Agent, second: &mut dyn Agent, rounds: usize) -> usize {
+        (0..rounds)
+            .filter(|_| {
+                play_round(new_round(), [&mut *first, &mut *second])
+                    .unwrap()
+                    .winner
+                    == "1"
+            })
+            .count()
+    }
+
+    #[test]
+    fn legal_actions_at_start() {
+        let instance = new_round();
+        let players = instance.lock().unwrap().players.clone();
+        let client = SchnapsenDuoClient::new(players[0].clone(), instance.clone());
+        let opponent = SchnapsenDuoClient::new(players[1].clone(), instance.clone());
+
+        let legal = client.legal_actions();
+        assert_eq!(
+            legal
+                .iter()
+                .filter(|action| matches!(action, Action::PlayCard(_)))
+                .count(),
+            5
+        );
+        assert!(legal.contains(&Action::CloseTalon));
+        assert!(opponent.legal_actions().is_empty());
+
+        let observation = client.observe().unwrap();
+        assert_eq!(observation.hand.len(), 5);
+        assert_eq!(observation.deck_size, 9);
+        assert_eq!(observation.unseen().len(), 14);
+    }
+
+    #[test]
+    fn agents_finish_rounds() {
+        let mut random = RandomAgent::seeded(7);
+        let mut greedy = GreedyAgent::new();
+        let mut beginner = BeginnerAgent::new();
+
+        wins(&mut random, &mut greedy, 50);
+        wins(&mut greedy, &mut beginner, 50);
+        wins(&mut beginner, &mut random, 50);
+    }
+
+    #[test]
+    fn engine_records_the_round() {
+        let instance = new_round();
+        play_round(
+            instance.clone(),
+            [&mut GreedyAgent::new(), &mut RandomAgent::seeded(11)],
+        )
+        .unwrap();
+
+        let record = instance.lock().unwrap().record().clone();
+        assert_eq!(record.players, ["1".to_string(), "2".to_string()]);
+        assert!(record.hands.iter().all(|hand| hand.len() == 5));
+        assert!(record.trump.is_some());
+        assert_eq!(record.talon.len(), 9);
+        assert_eq!(record.moves[0].player, "1");
+        assert!(record
+            .moves
+            .iter()
+            .any(|step| matches!(step.action, Action::PlayCard(_))));
+        assert_eq!(record.to_string().parse::<GameRecord>().unwrap(), record);
+    }
+
+    #[test]
+    fn seeded_games_deal_the_same_cards() {
+        let deal = |seed| {
+            let instance = SchnapsenDuo::seeded(&["1".to_string(), "2".to_string()], seed);
+            let instance = Arc::new(Mutex::new(instance));
+            let first = instance.lock().unwrap().players[0].clone();
+            instance
+                .lock()
+                .unwrap()
+                .set_active_player(first.clone())
+                .unwrap();
+            instance.lock().unwrap().distribute_cards().unwrap();
+            play_round(
+                instance.clone(),
+                [&mut RandomAgent::seeded(1), &mut RandomAgent::seeded(2)],
+            )
+            .unwrap();
+            instance.lock().unwrap().next_round(first);
+            let record = instance.lock().unwrap().record().clone();
+            record
+        };
+        assert_eq!(deal(4), deal(4));
+        assert_ne!(deal(4).hands, deal(5).hands);
+    }
+
+    #[test]
+    fn agents_by_name() {
+        for name in ["random", "greedy", "beginner", "ismcts", "ismcts:10"] {
+            assert!(crate::agent::by_name(name, 0).is_some(), "{name}");
+        }
+        assert!(crate::agent::by_name("ismcts:many", 0).is_none());
+        assert!(crate::agent::by_name("grandmaster", 0).is_none());
+    }
+
+    #[test]
+    fn personas_play_known_agents() {
+        for persona in crate::agent::PERSONAS {
+            assert!(crate::agent::by_name(persona.agent, 0).is_some(), "{persona:?}");
+            assert_eq!(crate::agent::Persona::find(persona.display_name), Some(&persona));
+        }
+        assert!(crate::agent::Persona::find("Nobody").is_none());
+    }
+
+    #[test]
+    fn greedy_beats_random() {
+        let mut greedy = GreedyAgent::new();
+        let mut random = RandomAgent::seeded(42);
+        assert!(wins(&mut greedy, &mut random, 400) > 220);
+    }
+
+    /// Checks every decision of the wrapped agent against a sampled `GameState`.
+    struct Mirrored<A: Agent>(A, StdRng);
+
+    impl<A: Agent> Agent for Mirrored<A> {
+        fn act(&mut self, observation: &Observation, legal: &[Action]) -> Action {
+            let state = GameState::sample(observation, &mut self.1);
+            assert_eq!(
+                state.hands[1].len() as usize,
+                observation.opponent_hand_size
+            );
+            assert_eq!(state.talon.len(), observation.deck_size);
+            let mirrored = state.legal_actions();
+            assert_eq!(mirrored.len(), legal.len(), "{observation:?}");
+            assert!(
+                mirrored.iter().all(|action| legal.contains(action)),
+                "{observation:?}"
+            );
+            self.0.act(observation, legal)
+        }
+    }
+
+    /// Replays a recorded round through `GameState`, where the players keep the record's order.
+    fn replay(record: &GameRecord) -> GameState {
+        let index = |id: &str| {
+            record
+                .players
+                .iter()
+                .position(|player| player == id)
+                .unwrap()
+        };
+        let mut state = GameState::new(
+            record.hands.clone().map(|hand| hand.into_iter().collect()),
+            record.trump.unwrap(),
+            record.talon.iter().rev().copied().collect(),
+            index(&record.moves[0].player),
+        );
+        for step in &record.moves {
+            assert_eq!(state.to_move, index(&step.player), "{record}");
+            state.apply(step.action).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn game_state_matches_engine() {
+        let mut first = Mirrored(RandomAgent::seeded(3), StdRng::seed_from_u64(1));
+        let mut second = Mirrored(GreedyAgent::new(), StdRng::seed_from_u64(2));
+        for round in 0..200 {
+            let instance = new_round();
+            let agents: [&mut dyn Agent; 2] = if round % 2 == 0 {
+                [&mut first, &mut second]
+            } else {
+                [&mut second, &mut first]
+            };
+            let outcome = play_round(instance.clone(), agents).unwrap();
+
+            let record = instance.lock().unwrap().record().clone();
+            let result = replay(&record)
+                .result
+                .expect("the replayed round is finished");
+            assert_eq!(record.players[result.winner], outcome.winner, "{record}");
+            assert_eq!(result.game_points, outcome.points, "{record}");
+        }
+    }
+
+    #[test]
+    fn ismcts_finishes_rounds() {
+        let config = IsmctsConfig {
+            budget: Budget::Iterations(50),
+            ..Default::default()
+        };
+        let mut ismcts = IsmctsAgent::seeded(config, 1);
+        let mut greedy = GreedyAgent::new();
+        wins(&mut ismcts, &mut greedy, 5);
+        wins(&mut greedy, &mut ismcts, 5);
+    }
+
+    /// Run with `cargo test --release -- --ignored ismcts_beats_greedy --nocapture`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn ismcts_beats_greedy() {
+        let rounds = 200;
+        let mut ismcts = IsmctsAgent::seeded(IsmctsConfig::default(), 5);
+        let mut greedy = GreedyAgent::new();
+
+        let won = wins(&mut ismcts, &mut greedy, rounds / 2)
+            + (rounds / 2 - wins(&mut greedy, &mut ismcts, rounds / 2));
+        println!("ismcts won {won} of {rounds} rounds against greedy");
+        assert!(won > rounds * 55 / 100);
+    }
+}
+
+mod engine_rules {
+    use std::sync::{Arc, Mutex, RwLock};
+    use std::time::Duration;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::helpers::{card, card_vec, cards, fixed_round};
+    use crate::client::SchnapsenDuoClient;
+    use crate::models::{Action, AnnounceType, Announcement, CardSuit, Player};
+    use crate::state::GameState;
+    use crate::{Opening, SchnapsenDuo};
+
+    fn play(instance: &mut SchnapsenDuo, player: &Arc<RwLock<Player>>, card: &str) {
+        instance
+            .play_card(player.clone(), self::card(card))
+            .unwrap();
+    }
+
+    fn twenty(suit: CardSuit) -> Announcement {
+        Announcement::new(AnnounceType::Twenty, suit)
+    }
+
+    #[test]
+    fn leader_can_announce_after_winning_a_trick() {
+        let mut instance = fixed_round(["SA CQ HT HJ DA", "SJ SQ SK CJ CA"], "DT", "HK CK");
+        let [first, second] = instance.players.clone();
+
+        play(&mut instance, &first, "SA");
+        play(&mut instance, &second, "SJ");
+
+        assert!(instance.is_active(&first.read().unwrap()));
+        let first = first.read().unwrap();
+        assert!(first.cards.contains(&card("CK")));
+        assert_eq!(first.announcable, vec![twenty(CardSuit::Clubs)]);
+    }
+
+    #[test]
+    fn losing_one_marriage_keeps_the_others_announcable() {
+        let mut instance = fixed_round(["HQ HK CQ CK SA", "HA SJ SQ DJ DQ"], "DT", "HJ DK ST DA");
+        let [first, second] = instance.players.clone();
+        assert_eq!(
+            first.read().unwrap().announcable,
+            vec![twenty(CardSuit::Hearts), twenty(CardSuit::Clubs)]
+        );
+
+        play(&mut instance, &first, "HQ");
+        play(&mut instance, &second, "HA");
+        play(&mut instance, &second, "SJ");
+
+        assert_eq!(
+            first.read().unwrap().announcable,
+            vec![twenty(CardSuit::Clubs)]
+        );
+    }
+
+    #[test]
+    fn closed_talon_forces_taking_the_trick() {
+        let mut instance = fixed_round(["HK SA CQ CK DA", "HA HJ SJ SQ CJ"], "DT", "HT ST DK DJ");
+        let [first, second] = instance.players.clone();
+        instance.close_talon(first.clone()).unwrap();
+
+        play(&mut instance, &first, "HK");
+
+        assert_eq!(second.read().unwrap().playable_cards, card_vec("HA"));
+    }
+
+    #[test]
+    fn announcing_keeps_the_other_marriages() {
+        let instance = fixed_round(["HQ HK CQ CK SA", "HA SJ SQ DJ DQ"], "DT", "HJ DK ST DA");
+        let [first, _] = instance.players.clone();
+        let instance = Arc::new(Mutex::new(instance));
+        let client = SchnapsenDuoClient::new(first.clone(), instance.clone());
+
+        client.announce_20(twenty(CardSuit::Hearts).cards).unwrap();
+
+        assert_eq!(
+            first.read().unwrap().announcable,
+            vec![twenty(CardSuit::Clubs)]
+        );
+        let announced = card_vec("HQ HK");
+        assert_eq!(
+            client.legal_actions(),
+            announced
+                .iter()
+                .copied()
+                .map(Action::PlayCard)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn conceding_gives_the_round_to_the_opponent() {
+        let mut instance = fixed_round(["SA CQ HT HJ DA", "SJ SQ SK CJ CA"], "DT", "HK CK");
+        let [first, second] = instance.players.clone();
+        play(&mut instance, &first, "SA");
+        play(&mut instance, &second, "SJ");
+
+        instance.concede_round(first.clone()).unwrap();
+
+        let outcome = instance.round_outcome().unwrap();
+        assert_eq!((outcome.winner.as_str(), outcome.points), ("2", 2));
+        assert_eq!(second.read().unwrap().points, 2);
+        assert!(instance.concede_round(second).is_err());
+    }
+
+    #[test]
+    fn opened_rounds_wait_for_the_cut_and_the_draw() {
+        let mut instance = SchnapsenDuo::seeded(&["1".to_string(), "2".to_string()], 7);
+        let [first, second] = instance.players.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        instance.on_priv_event(first.clone(), move |event| {
+            let _ = tx.send(format!("{event:?}"));
+        });
+        let next = || rx.recv_timeout(Duration::from_secs(1)).unwrap();
+
+        instance.set_active_player(first.clone()).unwrap();
+        instance.open_round().unwrap();
+        assert_eq!(next(), "AllowCuttDeck");
+        assert_eq!(instance.opening(), Some(Opening::Cut));
+        assert_eq!(instance.legal_actions(first.clone()).len(), 19);
+        assert!(instance.legal_actions(second.clone()).is_empty());
+        assert!(instance.observe(first.clone()).is_none());
+        assert!(instance.take_cards_til(first.clone(), 3).is_err());
+
+        instance.cutt_deck(first.clone(), 10).unwrap();
+        assert_eq!(next(), "AllowDrawCard");
+        assert_eq!(
+            instance.legal_actions(first.clone()),
+            [Action::TakeCards(3), Action::TakeCards(2)]
+        );
+        assert!(instance.cutt_deck(first.clone(), 10).is_err());
+        assert!(instance.take_cards_til(second.clone(), 2).is_err());
+        assert!(instance.take_cards_til(first.clone(), 4).is_err());
+
+        instance.take_cards_til(first.clone(), 2).unwrap();
+        assert_eq!(instance.opening(), None);
+        assert_eq!(first.read().unwrap().cards.len(), 5);
+        assert_eq!(second.read().unwrap().cards.len(), 5);
+        assert!(instance.observe(first.clone()).is_some());
+        assert!(instance.open_round().is_err());
+        let actions: Vec<_> = instance.record().moves.iter().map(|m| m.action).collect();
+        assert_eq!(actions, [Action::CuttDeck(10), Action::TakeCards(2)]);
+
+        // The next round is opened by its winner before anything is dealt
+        instance.open_next_round(second.clone());
+        assert_eq!(instance.opening(), Some(Opening::Cut));
+        assert!(second.read().unwrap().cards.is_empty());
+        assert!(instance.legal_actions(first.clone()).is_empty());
+        assert_eq!(instance.legal_actions(second.clone()).len(), 19);
+    }
+
+    #[test]
+    fn clearing_callbacks_releases_what_they_hold() {
+        let mut instance = fixed_round(["SA CQ HT HJ DA", "SJ SQ SK CJ CA"], "DT", "HK CK");
+        let first = instance.players[0].clone();
+        let owner = Arc::new(());
+        {
+            let owner = owner.clone();
+            instance.on_pub_event(move |_| drop(owner.clone()));
+        }
+        {
+            let owner = owner.clone();
+            instance.on_priv_event(first, move |_| drop(owner.clone()));
+        }
+        assert_eq!(Arc::strong_count(&owner), 3);
+
+        instance.clear_callbacks();
+
+        assert_eq!(Arc::strong_count(&owner), 1);
+    }
+
+    #[test]
+    fn samples_keep_the_swapped_trump_with_the_opponent() {
+        let instance = fixed_round(
+            ["DJ SA CQ CK HA", "HK SJ SQ CJ CA"],
+            "DT",
+            "HJ HQ HT DQ DK DA CT SK ST",
+        );
+        let [first, second] = instance.players.clone();
+        let instance = Arc::new(Mutex::new(instance));
+        let client = SchnapsenDuoClient::new(first, instance.clone());
+        client.perform(Action::SwapTrump(card("DJ"))).unwrap();
+        client.perform(Action::PlayCard(card("SA"))).unwrap();
+
+        let observation = instance.lock().unwrap().observe(second).unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let state = GameState::sample(&observation, &mut rng);
+            assert!(state.hands[1].contains(card("DT")));
+        }
+    }
+
+    #[test]
+    fn observations_know_what_the_events_told() {
+        let mut instance = fixed_round(
+            ["DJ SA CQ CK HA", "HK HQ HJ CJ CA"],
+            "DT",
+            "SJ SQ HT DQ DK DA CT SK ST",
+        );
+        let [first, second] = instance.players.clone();
+        instance.swap_trump(first.clone(), card("DJ")).unwrap();
+        instance.close_talon(first.clone()).unwrap();
+        play(&mut instance, &first, "SA");
+        play(&mut instance, &second, "HK");
+
+        let observation = instance.observe(first.clone()).unwrap();
+        assert!(observation.opponent_revealed.is_empty());
+        assert_eq!(
+            observation.opponent_ruled_out,
+            cards("SJ SQ SK ST DQ DK DA")
+        );
+        let observation = instance.observe(second.clone()).unwrap();
+        assert_eq!(observation.opponent_revealed, cards("DT"));
+        assert!(observation.opponent_ruled_out.is_empty());
+
+        // A new round starts without any knowledge of the last one
+        instance.recreate_deck();
+        instance.distribute_cards().unwrap();
+        let observation = instance.observe(second).unwrap();
+        assert!(observation.opponent_revealed.is_empty());
+        assert!(observation.opponent_ruled_out.is_empty());
+    }
+
+    #[test]
+    fn samples_respect_voids_after_closing() {
+        let mut instance = fixed_round(
+            ["HK SA CQ CK DA", "SJ SQ CJ CA ST"],
+            "DT",
+            "HJ HQ HT HA DJ DQ DK CT SK",
+        );
+        let [first, second] = instance.players.clone();
+        instance.close_talon(first.clone()).unwrap();
+        play(&mut instance, &first, "HK");
+        play(&mut instance, &second, "SJ");
+
+        let observation = instance.observe(first).unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let state = GameState::sample(&observation, &mut rng);
+            assert!(state.hands[1].of_suit(CardSuit::Hearts).is_empty());
+            assert!(state.hands[1].of_suit(CardSuit::Diamonds).is_empty());
+        }
+    }
+}
+
+mod solver {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::helpers::{cards, endgame};
+    use crate::models::{Action, CardSet, CardSuit};
+    use crate::solver::Solver;
+    use crate::state::{GameState, RoundResult};
+
+    /// Plain minimax without pruning or a table, in game points won by player `0`.
+    fn minimax(state: &GameState) -> i8 {
+        if let Some(result) = state.result {
+            let points = result.game_points as i8;
+            return if result.winner == 0 { points } else { -points };
+        }
+        let values = state.legal_actions().into_iter().map(|action| {
+            let mut next = state.clone();
+            next.apply(action).unwrap();
+            minimax(&next)
+        });
+        if state.to_move == 0 {
+            values.max().unwrap()
+        } else {
+            values.min().unwrap()
+        }
+    }
+
+    #[test]
+    fn takes_the_last_trick() {
+        let state = endgame(["HA", "HJ"], ["CA CT", "SK"], CardSuit::Hearts);
+
+        let solution = Solver::new().solve(&state).unwrap();
+        assert_eq!(solution.action, Action::PlayCard("HA".parse().unwrap()));
+        assert_eq!(
+            solution.result,
+            RoundResult {
+                winner: 0,
+                game_points: 2
+            }
+        );
+    }
+
+    #[test]
+    fn finds_the_only_winning_lead() {
+        // Leading the jack gives the opponent the lead and the trump trick
+        let state = endgame(
+            ["SA SJ", "ST HJ"],
+            ["HA HT CA CT DA", "DT DK DQ HK HQ"],
+            CardSuit::Hearts,
+        );
+
+        let solution = Solver::new().solve(&state).unwrap();
+        assert_eq!(solution.action, Action::PlayCard("SA".parse().unwrap()));
+        assert_eq!(
+            solution.result,
+            RoundResult {
+                winner: 0,
+                game_points: 2
+            }
+        );
+
+        let mut worse = state.clone();
+        worse
+            .apply(Action::PlayCard("SJ".parse().unwrap()))
+            .unwrap();
+        let mut solver = Solver::new();
+        assert_eq!(
+            solver.solve(&worse).unwrap().result,
+            RoundResult {
+                winner: 1,
+                game_points: 1
+            }
+        );
+    }
+
+    #[test]
+    fn announces_to_finish_the_round() {
+        let mut state = endgame(
+            ["HK HQ SJ", "HT HA SA"],
+            ["CA CT DA", "DT DK"],
+            CardSuit::Hearts,
+        );
+        state.closed_by = Some(0);
+
+        let solution = Solver::new().solve(&state).unwrap();
+        assert_eq!(solution.action, Action::Announce40);
+        assert_eq!(
+            solution.result,
+            RoundResult {
+                winner: 0,
+                game_points: 2
+            }
+        );
+    }
+
+    #[test]
+    fn refuses_positions_with_cards_to_draw() {
+        let state = GameState::new(
+            [cards("HA HT HK HQ HJ"), cards("SA ST SK SQ SJ")],
+            "DJ".parse().unwrap(),
+            cards("CA CT CK CQ CJ DA DT DK DQ").to_vec(),
+            0,
+        );
+        assert_eq!(Solver::new().solve(&state), None);
+    }
+
+    #[test]
+    fn matches_minimax_on_random_endgames() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut solver = Solver::new();
+        let mut solved = 0;
+
+        while solved < 100 {
+            let mut deck = CardSet::FULL.to_vec();
+            deck.shuffle(&mut rng);
+            let hands = [
+                deck.drain(..5).collect::<CardSet>(),
+                deck.drain(..5).collect::<CardSet>(),
+            ];
+            let trump_card = deck.remove(0);
+            let mut state = GameState::new(hands, trump_card, deck, rng.gen_range(0..2));
+
+            while !state.is_terminal() && !state.is_talon_exhausted() {
+                let legal = state.legal_actions();
+                state.apply(*legal.choose(&mut rng).unwrap()).unwrap();
+            }
+            let Some(solution) = solver.solve(&state) else {
+                continue;
+            };
+            solved += 1;
+
+            let value = minimax(&state);
+            assert_eq!(solution.result.winner, usize::from(value < 0));
+            assert_eq!(solution.result.game_points, value.unsigned_abs());
+
+            let mut next = state.clone();
+            next.apply(solution.action).unwrap();
+            assert_eq!(minimax(&next), value);
+        }
+    }
+}
+
+mod knowledge {
+    use std::collections::HashMap;
+
+    use super::helpers::{card, cards, dealt};
+    use crate::knowledge::{CardLocation, KnowledgeTracker};
+    use crate::models::{AnnounceType, Announcement, CardSuit};
+    use crate::{PrivateEvent, PublicEvent};
+
+    fn play(tracker: &mut KnowledgeTracker, user_id: &str, card: &str) {
+        tracker.on_pub_event(&PublicEvent::PlayCard {
+            user_id: user_id.to_string(),
+            card: self::card(card),
+        });
+    }
+
+    fn trick(tracker: &mut KnowledgeTracker, winner: &str, lead: &str, response: &str) {
+        tracker.on_pub_event(&PublicEvent::Trick {
+            user_id: winner.to_string(),
+            cards: [card(lead), card(response)],
+        });
+    }
+
+    #[test]
+    fn locates_the_dealt_cards() {
+        let tracker = dealt("HA HT CK CQ SJ", "DQ");
+        assert_eq!(tracker.location(card("HA")), CardLocation::Hand);
+        assert_eq!(tracker.location(card("DQ")), CardLocation::Trump);
+        assert_eq!(tracker.location(card("DA")), CardLocation::Unknown);
+        assert_eq!(tracker.unseen().len(), 14);
+        assert!(tracker.opponent_cards().is_empty());
+    }
+
+    #[test]
+    fn swapped_trump_is_with_the_opponent() {
+        let mut tracker = dealt("HA HT CK CQ SJ", "DQ");
+        tracker.on_pub_event(&PublicEvent::TrumpChange(Some(card("DJ"))));
+
+        assert_eq!(tracker.location(card("DQ")), CardLocation::Opponent);
+        assert_eq!(tracker.location(card("DJ")), CardLocation::Trump);
+    }
+
+    #[test]
+    fn own_swap_keeps_the_trump_in_hand() {
+        let mut tracker = dealt("HA HT CK CQ DJ", "DQ");
+        tracker.on_priv_event(&PrivateEvent::CardAvailabe(card("DQ")));
+        tracker.on_priv_event(&PrivateEvent::CardUnavailabe(card("DJ")));
+        tracker.on_pub_event(&PublicEvent::TrumpChange(Some(card("DJ"))));
+
+        assert_eq!(tracker.location(card("DQ")), CardLocation::Hand);
+        assert!(tracker.opponent_cards().is_empty());
+    }
+
+    #[test]
+    fn announced_cards_are_with_the_opponent_until_played() {
+        let mut tracker = dealt("HA HT CK CQ SJ", "DQ");
+        tracker.on_pub_event(&PublicEvent::Announce {
+            user_id: "2".to_string(),
+            announcement: Announcement::new(AnnounceType::Twenty, CardSuit::Spades),
+        });
+        assert_eq!(tracker.opponent_cards(), cards("SQ SK"));
+
+        play(&mut tracker, "2", "SQ");
+        assert_eq!(tracker.location(card("SQ")), CardLocation::Played);
+        assert_eq!(tracker.opponent_cards(), cards("SK"));
+    }
+
+    #[test]
+    fn failing_to_follow_after_the_close_reveals_voids() {
+        let mut tracker = dealt("HK SA CQ CK DA", "DT");
+        tracker.on_pub_event(&PublicEvent::CloseTalon {
+            user_id: "1".to_string(),
+        });
+        play(&mut tracker, "1", "HK");
+        play(&mut tracker, "2", "SJ");
+        trick(&mut tracker, "1", "HK", "SJ");
+
+        for card in tracker.unseen() {
+            let expected = if matches!(card.suit, CardSuit::Hearts | CardSuit::Diamonds) {
+                CardLocation::Talon
+            } else {
+                CardLocation::Unknown
+            };
+            assert_eq!(tracker.location(card), expected, "{card:?}");
+        }
+    }
+
+    #[test]
+    fn following_without_taking_after_the_close_reveals_lower_cards() {
+        let mut tracker = dealt("HK SA CQ CK DA", "DT");
+        tracker.on_pub_event(&PublicEvent::CloseTalon {
+            user_id: "1".to_string(),
+        });
+        trick(&mut tracker, "1", "HK", "HJ");
+
+        assert_eq!(tracker.ruled_out(), cards("HT HA"));
+        assert_eq!(tracker.location(card("HQ")), CardLocation::Unknown);
+    }
+
+    #[test]
+    fn own_responses_reveal_nothing() {
+        let mut tracker = dealt("HK SA CQ CK DA", "DT");
+        tracker.on_pub_event(&PublicEvent::CloseTalon {
+            user_id: "2".to_string(),
+        });
+        trick(&mut tracker, "2", "HA", "SA");
+        assert!(tracker.ruled_out().is_empty());
+    }
+
+    #[test]
+    fn exhausted_talon_reveals_the_opponents_hand() {
+        let mut tracker = dealt("HK SA CQ CK DA", "DT");
+        tracker.on_pub_event(&PublicEvent::TrumpChange(None));
+
+        assert!(!tracker.unseen().is_empty());
+        assert_eq!(tracker.opponent_cards(), tracker.unseen());
+        assert!(tracker.opponent_cards().contains(card("DT")));
+        assert!(tracker.is_talon_exhausted());
+    }
+
+    #[test]
+    fn drawing_the_trump_card_keeps_it_in_hand() {
+        let mut tracker = dealt("HK SA CQ CK DA", "DT");
+        tracker.on_pub_event(&PublicEvent::TrumpChange(None));
+        tracker.on_priv_event(&PrivateEvent::CardAvailabe(card("DT")));
+
+        assert_eq!(tracker.location(card("DT")), CardLocation::Hand);
+    }
+
+    #[test]
+    fn result_starts_a_new_round() {
+        let mut tracker = dealt("HK SA CQ CK DA", "DT");
+        tracker.on_pub_event(&PublicEvent::TrumpChange(Some(card("DJ"))));
+        tracker.on_pub_event(&PublicEvent::Result {
+            winner: "2".to_string(),
+            points: 1,
+            ranked: HashMap::new(),
+        });
+
+        assert_eq!(tracker.player_id(), "1");
+        assert!(tracker.hand().is_empty());
+        assert!(tracker.opponent_cards().is_empty());
+        assert_eq!(tracker.trump(), None);
+    }
+}
+
+mod stats {
+    use serde_json::json;
+
+    use super::helpers::card;
+    use crate::models::Action;
+    use crate::notation::GameRecord;
+    use crate::stats::{Rate, Statistics};
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+    }
+
+    fn assert_rate(rate: Rate, expected: [f64; 3]) {
+        assert_close(rate.rate, expected[0]);
+        assert_close(rate.lower, expected[1]);
+        assert_close(rate.upper, expected[2]);
+    }
+
+    fn record(moves: &[(&str, Action)]) -> GameRecord {
+        let mut record = GameRecord::new(Statistics::IDS.map(String::from));
+        for (player, action) in moves {
+            record.push(player, *action);
+        }
+        record
+    }
+
+    #[test]
+    fn rates_have_the_wilson_interval() {
+        assert_rate(Rate::new(50, 100), [0.5, 0.40383, 0.59617]);
+        assert_rate(Rate::new(7, 20), [0.35, 0.18119, 0.56715]);
+        assert_rate(Rate::new(0, 10), [0.0, 0.0, 0.27754]);
+        assert_rate(Rate::new(10, 10), [1.0, 0.72246, 1.0]);
+        assert_eq!(
+            Rate::new(0, 0),
+            Rate {
+                rate: 0.0,
+                lower: 0.0,
+                upper: 1.0
+            }
+        );
+    }
+
+    #[test]
+    fn reports_the_frequencies_per_round() {
+        let agents = ["random".to_string(), "greedy".to_string()];
+        let mut statistics = Statistics::new();
+        let report = statistics.report(&agents, 3, false);
+        assert_eq!(report.agents[0].average_game_points, 0.0);
+        assert_eq!(report.agents[0].close_talon_rate, 0.0);
+
+        let first = record(&[
+            ("0", Action::CloseTalon),
+            ("1", Action::Announce20([card("HQ"), card("HK")])),
+            ("0", Action::Announce40),
+            ("0", Action::PlayCard(card("SA"))),
+        ]);
+        statistics.count_round(&first, 0, 2);
+        statistics.count_round(&record(&[("1", Action::Announce40)]), 1, 3);
+
+        let [random, greedy] = statistics.report(&agents, 3, false).agents;
+        assert_eq!(random.rounds_won, 1);
+        assert_rate(random.round_win_rate, [0.5, 0.09453, 0.90547]);
+        assert_eq!(random.average_game_points, 1.0);
+        assert_eq!(random.close_talon_rate, 0.5);
+        assert_eq!(random.announcement_rate, 0.5);
+        assert_eq!(greedy.average_game_points, 1.5);
+        assert_eq!(greedy.close_talon_rate, 0.0);
+        assert_eq!(greedy.announcement_rate, 1.0);
+    }
+
+    #[test]
+    fn reports_as_json() {
+        let agents = ["random".to_string(), "greedy".to_string()];
+        let mut statistics = Statistics::new();
+        statistics.count_round(&record(&[("1", Action::CloseTalon)]), 1, 3);
+
+        let rate =
+            |rate: Rate| json!({ "rate": rate.rate, "lower": rate.lower, "upper": rate.upper });
+        let report = serde_json::to_value(statistics.report(&agents, 7, false)).unwrap();
+        assert_eq!(
+            report,
+            json!({
+                "seed": 7,
+                "rounds": 1,
+                "agents": [
+                    {
+                        "agent": "random",
+                        "rounds_won": 0,
+                        "round_win_rate": rate(Rate::new(0, 1)),
+                        "average_game_points": 0.0,
+                        "close_talon_rate": 0.0,
+                        "announcement_rate": 0.0,
+                    },
+                    {
+                        "agent": "greedy",
+                        "rounds_won": 1,
+                        "round_win_rate": rate(Rate::new(1, 1)),
+                        "average_game_points": 3.0,
+                        "close_talon_rate": 1.0,
+                        "announcement_rate": 0.0,
+                    },
+                ],
+            })
+        );
+
+        // The bummerls are only reported if they were played
+        statistics.count_bummerl(1);
+        let report = serde_json::to_value(statistics.report(&agents, 7, true)).unwrap();
+        assert_eq!(report["bummerls"], 1);
+        assert_eq!(report["agents"][0]["bummerls_won"], 0);
+        assert_eq!(report["agents"][1]["bummerls_won"], 1);
+        assert_eq!(
+            report["agents"][1]["bummerl_win_rate"],
+            rate(Rate::new(1, 1))
+        );
+    }
 }
@@ -0,0 +1,290 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use schnapsen_duo_client::{Mirror, ServerEvent};
+use schnapsen_rs::{
+    models::{Action, AnnounceType, Card, CardVal},
+    PublicEvent,
+};
+
+/// Lines kept in the event log.
+const LOG_SIZE: usize = 200;
+
+/// What the player asks the server for.
+#[derive(Debug, Clone, Copy)]
+pub enum Command {
+    Perform(Action),
+    DrawCard,
+    Quit,
+    /// Replays the events of the round.
+    Sync,
+}
+
+/// A move the player is being forced to make within `timeout`.
+#[derive(Debug, Clone, Copy)]
+pub struct Countdown {
+    pub deadline: Instant,
+    pub timeout: Duration,
+}
+
+impl Countdown {
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+}
+
+/// The match as the player sees it and the state of the screen.
+pub struct App {
+    pub match_id: String,
+    pub mirror: Mirror,
+    pub countdown: Option<Countdown>,
+    pub log: VecDeque<String>,
+    pub selected: usize,
+    /// The command being typed after `:`.
+    pub input: Option<String>,
+    pub connected: bool,
+    pub exit: bool,
+}
+
+impl App {
+    pub fn new(match_id: String) -> Self {
+        Self {
+            match_id,
+            // The server tells the seat once it accepted the token
+            mirror: Mirror::new(String::new()),
+            countdown: None,
+            log: VecDeque::new(),
+            selected: 0,
+            input: None,
+            connected: false,
+            exit: false,
+        }
+    }
+
+    pub fn log(&mut self, line: impl Into<String>) {
+        if self.log.len() == LOG_SIZE {
+            self.log.pop_front();
+        }
+        self.log.push_back(line.into());
+    }
+
+    /// The own hand, in the order it is shown.
+    pub fn hand(&self) -> Vec<Card> {
+        self.mirror.hand().iter().collect()
+    }
+
+    /// A short name for a user id.
+    pub fn name<'a>(&self, user_id: &'a str) -> &'a str {
+        if user_id == self.mirror.player_id() {
+            "you"
+        } else {
+            user_id
+        }
+    }
+
+    /// Forgets the round before it is replayed by the server.
+    pub fn reset_round(&mut self) {
+        self.mirror.reset_round();
+        self.countdown = None;
+        self.selected = 0;
+    }
+
+    pub fn on_server_event(&mut self, event: ServerEvent) {
+        self.mirror.on_event(&event);
+        match event {
+            ServerEvent::Public(event) => self.on_pub_event(event.event),
+            ServerEvent::Private(_) => {}
+            ServerEvent::TimeoutThreat { timeout, .. } => {
+                let timeout = Duration::from_secs(timeout);
+                self.countdown = Some(Countdown {
+                    deadline: Instant::now() + timeout,
+                    timeout,
+                });
+            }
+            ServerEvent::TimeoutWarning { remaining } => {
+                self.log(format!("only {remaining} seconds left to move"))
+            }
+            ServerEvent::TimeoutThreatCancelled => self.countdown = None,
+            ServerEvent::Timeout { user_id, reason } => {
+                self.countdown = None;
+                let line = format!("{} timed out: {reason}", self.name(&user_id));
+                self.log(line);
+            }
+            ServerEvent::Reset => {
+                self.reset_round();
+                self.log("the next round is about to be dealt");
+            }
+            ServerEvent::Seat { user_id } => self.log(format!("playing as {user_id}")),
+            ServerEvent::Error(message) => self.log(format!("error: {message}")),
+            ServerEvent::Connected => {
+                self.reset_round();
+                self.connected = true;
+                self.log(format!("joined match {}", self.match_id));
+            }
+            ServerEvent::Disconnected => {
+                self.connected = false;
+                self.log("disconnected");
+            }
+        }
+        let hand = self.mirror.hand().len() as usize;
+        self.selected = self.selected.min(hand.saturating_sub(1));
+    }
+
+    /// Logs what happened on the table, the mirror already counted it.
+    fn on_pub_event(&mut self, event: PublicEvent) {
+        let line = match event {
+            PublicEvent::Trick { user_id, cards } => {
+                format!("{} took {:#} {:#}", self.name(&user_id), cards[0], cards[1])
+            }
+            PublicEvent::Announce {
+                user_id,
+                announcement,
+            } => format!("{} announced {announcement:#}", self.name(&user_id)),
+            PublicEvent::CloseTalon { user_id } => {
+                format!("{} closed the talon", self.name(&user_id))
+            }
+            PublicEvent::TrumpChange(Some(card)) => format!("the trump card is {card:#}"),
+            PublicEvent::Result { winner, points, .. } => {
+                self.countdown = None;
+                format!(
+                    "{} won the round and {points} game points",
+                    self.name(&winner)
+                )
+            }
+            PublicEvent::FinalResult { winner, .. } => {
+                format!("{} won the bummerl", self.name(&winner))
+            }
+            _ => return,
+        };
+        self.log(line);
+    }
+
+    fn select(&mut self, index: usize) {
+        let hand = self.mirror.hand().len() as usize;
+        if hand > 0 {
+            self.selected = index.min(hand - 1);
+        }
+    }
+
+    /// The marriage of the selected card's suit, if it can be announced.
+    fn selected_announcement(&self) -> Option<Action> {
+        let card = *self.hand().get(self.selected)?;
+        let announcable = self.mirror.announcable();
+        let announcement = announcable
+            .iter()
+            .find(|announcement| announcement.cards[0].suit == card.suit)
+            .or_else(|| announcable.first())?;
+
+        Some(match announcement.announce_type {
+            AnnounceType::Forty => Action::Announce40,
+            AnnounceType::Twenty => Action::Announce20(announcement.cards),
+        })
+    }
+
+    /// Handles a key press and returns what has to be sent to the server.
+    pub fn on_key(&mut self, key: KeyEvent) -> Option<Command> {
+        if self.input.is_some() {
+            return self.on_input_key(key);
+        }
+
+        let hand = self.hand();
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.exit = true;
+                None
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.exit = true;
+                None
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.select(self.selected.saturating_sub(1));
+                None
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.select(self.selected + 1);
+                None
+            }
+            KeyCode::Char(digit @ '1'..='9') => {
+                let index = digit as usize - '1' as usize;
+                let card = *hand.get(index)?;
+                self.select(index);
+                Some(Command::Perform(Action::PlayCard(card)))
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let card = *hand.get(self.selected)?;
+                Some(Command::Perform(Action::PlayCard(card)))
+            }
+            KeyCode::Char('a') => match self.selected_announcement() {
+                Some(action) => Some(Command::Perform(action)),
+                None => {
+                    self.log("there is no marriage to announce");
+                    None
+                }
+            },
+            KeyCode::Char('s') => {
+                let jack = self.mirror.swap().or_else(|| {
+                    let trump = self.mirror.knowledge().trump()?;
+                    Some(Card::new(CardVal::Jack, trump))
+                })?;
+                Some(Command::Perform(Action::SwapTrump(jack)))
+            }
+            KeyCode::Char('c') => Some(Command::Perform(Action::CloseTalon)),
+            KeyCode::Char('d') => Some(Command::DrawCard),
+            KeyCode::Char('r') => Some(Command::Sync),
+            KeyCode::Char(':') => {
+                self.input = Some(String::new());
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn on_input_key(&mut self, key: KeyEvent) -> Option<Command> {
+        let input = self.input.as_mut()?;
+        match key.code {
+            KeyCode::Esc => {
+                self.input = None;
+                None
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                None
+            }
+            KeyCode::Char(char) => {
+                input.push(char);
+                None
+            }
+            KeyCode::Enter => {
+                let line = self.input.take().unwrap();
+                match parse_command(&line) {
+                    Ok(command) => command,
+                    Err(err) => {
+                        self.log(err);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parses the commands typed after `:`, which are the actions of `schnapsen_rs::notation` and
+/// `draw`, `sync` and `quit`.
+fn parse_command(line: &str) -> Result<Option<Command>, String> {
+    match line.trim() {
+        "" => Ok(None),
+        "draw" => Ok(Some(Command::DrawCard)),
+        "sync" => Ok(Some(Command::Sync)),
+        "quit" => Ok(Some(Command::Quit)),
+        action => action
+            .parse::<Action>()
+            .map(|action| Some(Command::Perform(action)))
+            .map_err(|err| err.to_string()),
+    }
+}
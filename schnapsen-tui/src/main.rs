@@ -0,0 +1,114 @@
+//! Plays a match of the duo server in the terminal.
+
+use std::{
+    env,
+    process::ExitCode,
+    sync::mpsc::{self, TryRecvError},
+    time::Duration,
+};
+
+use futures_util::StreamExt;
+use ratatui::{
+    crossterm::event::{self, Event, KeyEventKind},
+    DefaultTerminal,
+};
+use schnapsen_duo_client::{ClientError, DuoClient};
+use tokio::runtime::Runtime;
+
+mod app;
+mod ui;
+
+use app::{App, Command};
+
+const USAGE: &str = "usage: schnapsen-tui <server url> <match id> <write token>";
+
+/// How often the screen is redrawn while nothing happens, so the countdown keeps running.
+const TICK: Duration = Duration::from_millis(200);
+
+async fn send(client: &DuoClient, command: Command) -> Result<(), ClientError> {
+    match command {
+        Command::Perform(action) => client.perform(action).await,
+        Command::DrawCard => client.draw_card().await,
+        Command::Quit => client.quit().await,
+        Command::Sync => client.sync(None).await,
+    }
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    url: &str,
+    match_id: &str,
+    token: &str,
+) -> Result<(), String> {
+    let runtime = Runtime::new().map_err(|err| err.to_string())?;
+    let client = runtime
+        .block_on(DuoClient::connect(url, match_id, token))
+        .map_err(|err| err.to_string())?;
+
+    // The screen is drawn synchronously, so the events are handed over through a channel
+    let (sender, events) = mpsc::channel();
+    let mut stream = client.events();
+    runtime.spawn(async move {
+        while let Some(event) = stream.next().await {
+            if sender.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut app = App::new(match_id.to_string());
+
+    while !app.exit {
+        loop {
+            match events.try_recv() {
+                Ok(event) => app.on_server_event(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Err("the connection was lost".into()),
+            }
+        }
+
+        terminal
+            .draw(|frame| ui::draw(frame, &app))
+            .map_err(|err| err.to_string())?;
+
+        if !event::poll(TICK).map_err(|err| err.to_string())? {
+            continue;
+        }
+        if let Event::Key(key) = event::read().map_err(|err| err.to_string())? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(command) = app.on_key(key) {
+                if let Command::Sync = command {
+                    app.reset_round();
+                }
+                if let Err(err) = runtime.block_on(send(&client, command)) {
+                    app.log(format!("couldn't send: {err}"));
+                }
+            }
+        }
+    }
+
+    let _ = runtime.block_on(client.disconnect());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let [url, match_id, token] = args.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, url, match_id, token);
+    ratatui::restore();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
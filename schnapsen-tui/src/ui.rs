@@ -0,0 +1,190 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
+    Frame,
+};
+use schnapsen_rs::{
+    models::{Card, CardSuit},
+    Opening,
+};
+
+use crate::app::App;
+
+const HELP: &str = "←/→ select  enter play  1-9 play nth  a announce  s swap  c close  d draw  r sync  : command  q leave";
+
+fn card_span(card: Card) -> Span<'static> {
+    let color = match card.suit {
+        CardSuit::Hearts | CardSuit::Diamonds => Color::Red,
+        CardSuit::Clubs | CardSuit::Spades => Color::Reset,
+    };
+    Span::styled(format!("{card:#}"), Style::new().fg(color))
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [header, table, hand, log, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(6),
+        Constraint::Length(4),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_header(frame, app, header);
+    let [table, score] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(table);
+    draw_table(frame, app, table);
+    draw_score(frame, app, score);
+    draw_hand(frame, app, hand);
+    draw_log(frame, app, log);
+    draw_footer(frame, app, footer);
+}
+
+fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" match {} ", app.match_id));
+
+    let turn = match (app.mirror.active(), app.connected) {
+        (_, false) => "not connected".to_string(),
+        (_, true) if app.mirror.is_finished() => "the match is over".to_string(),
+        (Some(_), true) if app.mirror.is_my_turn() => "your turn".to_string(),
+        (Some(active), true) => format!("{}'s turn", app.name(active)),
+        (None, true) => "waiting".to_string(),
+    };
+
+    match app.countdown {
+        Some(countdown) => {
+            let remaining = countdown.remaining();
+            let ratio = if countdown.timeout.is_zero() {
+                0.0
+            } else {
+                remaining.as_secs_f64() / countdown.timeout.as_secs_f64()
+            };
+            let gauge = Gauge::default()
+                .block(block)
+                .gauge_style(Style::new().fg(Color::Yellow))
+                .ratio(ratio.clamp(0.0, 1.0))
+                .label(format!("{turn}, {}s left to move", remaining.as_secs()));
+            frame.render_widget(gauge, area);
+        }
+        None => frame.render_widget(Paragraph::new(turn).block(block), area),
+    }
+}
+
+fn draw_table(frame: &mut Frame, app: &App, area: Rect) {
+    let mut trump = vec![Span::raw("trump  ")];
+    let knowledge = app.mirror.knowledge();
+    match (knowledge.trump_card(), knowledge.trump()) {
+        (Some(card), _) => trump.push(card_span(card)),
+        (None, Some(suit)) => trump.push(Span::raw(format!("{suit:#}"))),
+        (None, None) => trump.push(Span::raw("-")),
+    }
+
+    let talon = match (app.mirror.closed_by(), app.mirror.deck_size()) {
+        (Some(closer), _) => format!("closed by {}", app.name(closer)),
+        (None, _) if knowledge.trump().is_none() => "-".to_string(),
+        (None, 0) => "empty".to_string(),
+        (None, count) => format!("{count} cards"),
+    };
+
+    let mut trick = vec![Span::raw("trick  ")];
+    for (user_id, card) in app.mirror.trick() {
+        trick.push(card_span(*card));
+        trick.push(Span::raw(format!(" by {}  ", app.name(user_id))));
+    }
+
+    let mut last = vec![Span::raw("last   ")];
+    if let Some((winner, cards)) = app.mirror.last_trick() {
+        last.push(card_span(cards[0]));
+        last.push(Span::raw(" "));
+        last.push(card_span(cards[1]));
+        last.push(Span::raw(format!(" to {}", app.name(winner))));
+    }
+
+    let lines = vec![
+        Line::from(trump),
+        Line::from(format!("talon  {talon}")),
+        Line::from(trick),
+        Line::from(last),
+    ];
+    let block = Block::default().borders(Borders::ALL).title(" table ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_score(frame: &mut Frame, app: &App, area: Rect) {
+    let mut players = vec![app.mirror.player_id()];
+    players.extend(app.mirror.opponent_id());
+
+    let lines: Vec<_> = players
+        .iter()
+        .map(|id| {
+            let points = app.mirror.points().get(*id).copied().unwrap_or(0);
+            let game_points = app.mirror.game_points().get(*id).copied().unwrap_or(0);
+            Line::from(format!(
+                "{:<12} {points:>3} points  {game_points} game points",
+                app.name(id)
+            ))
+        })
+        .collect();
+
+    let block = Block::default().borders(Borders::ALL).title(" score ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_hand(frame: &mut Frame, app: &App, area: Rect) {
+    let mut cards = Vec::new();
+    for (idx, card) in app.hand().into_iter().enumerate() {
+        let mut span = card_span(card);
+        if app.mirror.is_my_turn() && !app.mirror.playable().contains(card) {
+            span = span.add_modifier(Modifier::DIM);
+        }
+        if idx == app.selected {
+            span = span.add_modifier(Modifier::REVERSED);
+        }
+        cards.push(Span::raw(format!("{}:", idx + 1)));
+        cards.push(span);
+        cards.push(Span::raw("  "));
+    }
+
+    let mut options = Vec::new();
+    match app.mirror.opening().filter(|_| app.mirror.is_my_turn()) {
+        Some(Opening::Cut) => options.push(":cut <1-19>".to_string()),
+        Some(Opening::Draw) => options.push(":take <3|2>".to_string()),
+        None => {}
+    }
+    for announcement in app.mirror.announcable() {
+        options.push(format!("announce {announcement:#}"));
+    }
+    if let Some(jack) = app.mirror.swap() {
+        options.push(format!("swap {jack:#}"));
+    }
+
+    let lines = vec![Line::from(cards), Line::from(options.join(", ")).italic()];
+    let block = Block::default().borders(Borders::ALL).title(" hand ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_log(frame: &mut Frame, app: &App, area: Rect) {
+    // The newest lines which fit, without the borders
+    let height = area.height.saturating_sub(2) as usize;
+    let items: Vec<_> = app
+        .log
+        .iter()
+        .skip(app.log.len().saturating_sub(height))
+        .map(|line| ListItem::new(line.as_str()))
+        .collect();
+
+    let block = Block::default().borders(Borders::ALL).title(" log ");
+    frame.render_widget(List::new(items).block(block), area);
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let footer = match &app.input {
+        Some(input) => Paragraph::new(format!(":{input}")),
+        None => Paragraph::new(HELP).dim(),
+    };
+    frame.render_widget(footer, area);
+}